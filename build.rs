@@ -0,0 +1,15 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    // 测试框架需要内核的 C 实现作为参照
+    #[cfg(feature = "harness")]
+    {
+        println!("cargo:rerun-if-changed=src/tnum.c");
+        println!("cargo:rerun-if-changed=include/tnum.h");
+        println!("cargo:rerun-if-changed=include/shim/linux/kernel.h");
+        cc::Build::new()
+            .file("src/tnum.c")
+            .include("include/shim")
+            .include("include")
+            .compile("tnum_c");
+    }
+}
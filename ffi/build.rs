@@ -0,0 +1,17 @@
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=TNUM_FFI_UPDATE_HEADER");
+    let bindings = cbindgen::generate(&crate_dir).expect("failed to generate the C header");
+    // 默认只写到 OUT_DIR；源码树中的 include/tnum_rs.h 只在显式要求时更新，
+    // test_header_up_to_date 检查两者一致
+    bindings.write_to_file(out_dir.join("tnum_rs.h"));
+    if env::var_os("TNUM_FFI_UPDATE_HEADER").is_some() {
+        bindings.write_to_file(crate_dir.join("../include/tnum_rs.h"));
+    }
+}
@@ -0,0 +1,526 @@
+//! C ABI for the Rust tnum library
+//!
+//! Every transfer function of `tnum::tnum` is exported for 64-bit tnums as
+//! `rust_<name>`, so the library can be linked next to the kernel C code
+//! (`src/tnum.c`) without symbol clashes. `Tnum` is `#[repr(C)]` and is
+//! passed as the C `struct tnum`; `include/tnum_rs.h` is generated from this
+//! file by cbindgen. The build writes the header to `OUT_DIR` only; run
+//! `TNUM_FFI_UPDATE_HEADER=1 cargo build -p tnum-ffi` (`make header`) to
+//! update the checked-in copy, which `cargo test` checks is current.
+//!
+//! Like the kernel, add/sub/mul (every multiplier, not only `rust_tnum_mul`)
+//! wrap on overflow. Shift amounts must be smaller than the bit width (64,
+//! or 32 for the `_32` forms), byte-swap widths must be 16, 32 or 64 and
+//! `rust_tnum_sext` needs a non-zero width; anything else aborts, in debug
+//! and release builds alike. Rotation amounts are taken modulo the width.
+
+use tnum::tnum::*;
+
+/// `OverflowFlag` 的 C 表示
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnumOverflow {
+    /// 所有取值都不会回绕
+    Never,
+    /// 部分取值会回绕
+    Maybe,
+    /// 所有取值都会回绕
+    Always,
+}
+
+impl From<OverflowFlag> for TnumOverflow {
+    fn from(flag: OverflowFlag) -> Self {
+        match flag {
+            OverflowFlag::Never => TnumOverflow::Never,
+            OverflowFlag::Maybe => TnumOverflow::Maybe,
+            OverflowFlag::Always => TnumOverflow::Always,
+        }
+    }
+}
+
+/// `Endian` 的 C 表示
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnumEndian {
+    /// 小端
+    Little,
+    /// 大端
+    Big,
+}
+
+impl From<TnumEndian> for Endian {
+    fn from(endian: TnumEndian) -> Self {
+        match endian {
+            TnumEndian::Little => Endian::Little,
+            TnumEndian::Big => Endian::Big,
+        }
+    }
+}
+
+/// 带溢出标志的运算结果
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TnumOverflowing {
+    pub result: Tnum,
+    pub overflow: TnumOverflow,
+}
+
+impl From<(Tnum, OverflowFlag)> for TnumOverflowing {
+    fn from((result, flag): (Tnum, OverflowFlag)) -> Self {
+        TnumOverflowing { result, overflow: flag.into() }
+    }
+}
+
+/// 双倍宽度乘积的低半部分与高半部分
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TnumWide {
+    pub lo: Tnum,
+    pub hi: Tnum,
+}
+
+/// 计数类运算的取值范围 [min, max]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TnumBounds {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl From<(u32, u32)> for TnumBounds {
+    fn from((min, max): (u32, u32)) -> Self {
+        TnumBounds { min, max }
+    }
+}
+
+// 构造
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_const(value: u64) -> Tnum {
+    tnum_const(value)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_range(min: u64, max: u64) -> Tnum {
+    tnum_range(min, max)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_range_wrapped(min: u64, max: u64) -> Tnum {
+    tnum_range_wrapped(min, max)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_srange(min: i64, max: i64) -> Tnum {
+    tnum_srange(min, max)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_const_subreg(a: Tnum, value: u32) -> Tnum {
+    tnum_const_subreg(a, value)
+}
+
+// 移位与旋转
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_lshift(a: Tnum, shift: u8) -> Tnum {
+    assert!(shift < 64);
+    tnum_lshift(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rshift(a: Tnum, shift: u8) -> Tnum {
+    assert!(shift < 64);
+    tnum_rshift(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_arshift(a: Tnum, min_shift: u8, insn_bitness: u8) -> Tnum {
+    assert!(min_shift < 64);
+    tnum_arshift(a, min_shift, insn_bitness)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rotl(a: Tnum, shift: u8) -> Tnum {
+    tnum_rotl(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rotr(a: Tnum, shift: u8) -> Tnum {
+    tnum_rotr(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rotl_var(a: Tnum, shift: Tnum) -> Tnum {
+    tnum_rotl_var(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rotr_var(a: Tnum, shift: Tnum) -> Tnum {
+    tnum_rotr_var(a, shift)
+}
+
+// 算术
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_add(a: Tnum, b: Tnum) -> Tnum {
+    tnum_add_wrapping(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_sub(a: Tnum, b: Tnum) -> Tnum {
+    tnum_sub_wrapping(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_neg(a: Tnum) -> Tnum {
+    tnum_neg(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_abs(a: Tnum) -> Tnum {
+    tnum_abs(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_add_overflowing(a: Tnum, b: Tnum) -> TnumOverflowing {
+    tnum_add_overflowing(a, b).into()
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_sub_overflowing(a: Tnum, b: Tnum) -> TnumOverflowing {
+    tnum_sub_overflowing(a, b).into()
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_saturating_add(a: Tnum, b: Tnum) -> Tnum {
+    tnum_saturating_add(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_saturating_sub(a: Tnum, b: Tnum) -> Tnum {
+    tnum_saturating_sub(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_div(a: Tnum, b: Tnum) -> Tnum {
+    tnum_div(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mod(a: Tnum, b: Tnum) -> Tnum {
+    tnum_mod(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_umin(a: Tnum, b: Tnum) -> Tnum {
+    tnum_umin(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_umax(a: Tnum, b: Tnum) -> Tnum {
+    tnum_umax(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_smin(a: Tnum, b: Tnum) -> Tnum {
+    tnum_smin(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_smax(a: Tnum, b: Tnum) -> Tnum {
+    tnum_smax(a, b)
+}
+
+// 乘法
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mul(a: Tnum, b: Tnum) -> Tnum {
+    tnum_mul(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mul_opt(a: Tnum, b: Tnum) -> Tnum {
+    tnum_mul_opt(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mul_by_const(a: Tnum, c: u64) -> Tnum {
+    tnum_mul_by_const(a, c)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mul_rec(a: Tnum, b: Tnum) -> Tnum {
+    tnum_mul_rec(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_xtnum_mul_top(a: Tnum, b: Tnum) -> Tnum {
+    xtnum_mul_top(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_xtnum_mul_high_top(a: Tnum, b: Tnum) -> Tnum {
+    xtnum_mul_high_top(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mul_overflowing(a: Tnum, b: Tnum) -> TnumOverflowing {
+    tnum_mul_overflowing(a, b).into()
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mul_wide(a: Tnum, b: Tnum) -> TnumWide {
+    let (lo, hi) = tnum_mul_wide(a, b);
+    TnumWide { lo, hi }
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mulhi(a: Tnum, b: Tnum) -> Tnum {
+    tnum_mulhi(a, b)
+}
+
+// 位运算
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_and(a: Tnum, b: Tnum) -> Tnum {
+    tnum_and(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_or(a: Tnum, b: Tnum) -> Tnum {
+    tnum_or(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_xor(a: Tnum, b: Tnum) -> Tnum {
+    tnum_xor(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_popcount(a: Tnum) -> Tnum {
+    tnum_popcount(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_clz(a: Tnum) -> Tnum {
+    tnum_clz(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_ctz(a: Tnum) -> Tnum {
+    tnum_ctz(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_popcount_range(a: Tnum) -> TnumBounds {
+    tnum_popcount_range(a).into()
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_clz_range(a: Tnum) -> TnumBounds {
+    tnum_clz_range(a).into()
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_ctz_range(a: Tnum) -> TnumBounds {
+    tnum_ctz_range(a).into()
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_bswap16(a: Tnum) -> Tnum {
+    tnum_bswap16(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_bswap32(a: Tnum) -> Tnum {
+    tnum_bswap32(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_bswap64(a: Tnum) -> Tnum {
+    tnum_bswap64(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_to_le(a: Tnum, width: u8, target: TnumEndian) -> Tnum {
+    tnum_to_le(a, width, target.into())
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_to_be(a: Tnum, width: u8, target: TnumEndian) -> Tnum {
+    tnum_to_be(a, width, target.into())
+}
+
+// 格运算与查询
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_join(a: Tnum, b: Tnum) -> Tnum {
+    tnum_join(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_intersect(a: Tnum, b: Tnum) -> Tnum {
+    tnum_intersect(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_in(a: Tnum, b: Tnum) -> bool {
+    tnum_in(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_is_aligned(a: Tnum, size: u64) -> bool {
+    tnum_is_aligned(a, size)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_size(a: Tnum) -> u8 {
+    // 64 位 tnum 的位数不超过 64
+    tnum_size(a) as u8
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_cast(a: Tnum, size: u8) -> Tnum {
+    tnum_cast(a, size)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_sext(a: Tnum, from_bits: u8) -> Tnum {
+    tnum_sext(a, from_bits)
+}
+
+// 32 位子寄存器（ALU32）
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_subreg(a: Tnum) -> Tnum {
+    tnum_subreg(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_clear_subreg(a: Tnum) -> Tnum {
+    tnum_clear_subreg(a)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_with_subreg(reg: Tnum, subreg: Tnum) -> Tnum {
+    tnum_with_subreg(reg, subreg)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_add_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_add_32(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_sub_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_sub_32(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mul_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_mul_32(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_div_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_div_32(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_mod_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_mod_32(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_and_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_and_32(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_or_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_or_32(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_xor_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_xor_32(a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_lshift_32(a: Tnum, shift: u8) -> Tnum {
+    assert!(shift < 32);
+    tnum_lshift_32(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rshift_32(a: Tnum, shift: u8) -> Tnum {
+    assert!(shift < 32);
+    tnum_rshift_32(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_arshift_32(a: Tnum, shift: u8) -> Tnum {
+    assert!(shift < 32);
+    tnum_arshift_32(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rotl_32(a: Tnum, shift: u8) -> Tnum {
+    tnum_rotl_32(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rotr_32(a: Tnum, shift: u8) -> Tnum {
+    tnum_rotr_32(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rotl_var_32(a: Tnum, shift: Tnum) -> Tnum {
+    tnum_rotl_var_32(a, shift)
+}
+
+#[no_mangle]
+pub extern "C" fn rust_tnum_rotr_var_32(a: Tnum, shift: Tnum) -> Tnum {
+    tnum_rotr_var_32(a, shift)
+}
+
+#[test]
+fn test_ffi_layout() {
+    use std::mem::{align_of, offset_of, size_of};
+    // struct tnum { u64 value; u64 mask; }
+    assert_eq!(size_of::<Tnum>(), 16);
+    assert_eq!(align_of::<Tnum>(), align_of::<u64>());
+    assert_eq!(offset_of!(TnumWide, hi), 16);
+
+    let a = rust_tnum_range(0, 7);
+    assert_eq!((a.value(), a.mask()), (0, 7));
+    assert_eq!(rust_tnum_add(tnum_const(u64::MAX), tnum_const(1)), tnum_const(0));
+    assert_eq!(rust_tnum_mul(tnum_const(1 << 63), tnum_const(2)), tnum_const(0));
+    let r = rust_tnum_add_overflowing(a, tnum_const(u64::MAX));
+    assert_eq!(r.overflow, TnumOverflow::Maybe);
+    assert_eq!(rust_tnum_popcount_range(a), TnumBounds { min: 0, max: 3 });
+
+    // 乘积回绕时所有乘法都不能 panic（panic 无法穿过 extern "C"）
+    let (x, y) = (Tnum::new(1 << 63, 1), Tnum::new(6, 1));
+    let sound: [extern "C" fn(Tnum, Tnum) -> Tnum; 4] =
+        [rust_tnum_mul, rust_tnum_mul_opt, rust_xtnum_mul_top, rust_xtnum_mul_high_top];
+    for mul in sound {
+        let r = mul(x, y);
+        assert!(tnum_in(r, tnum_const((1u64 << 63).wrapping_mul(7))), "{:?}", r);
+        assert!(mul(Tnum::unknown(), Tnum::unknown()).is_top());
+    }
+    // tnum_mul_rec 尚不可靠，这里只检查不会 panic
+    rust_tnum_mul_rec(x, y);
+    assert_eq!(rust_tnum_mulhi(tnum_const(u64::MAX), tnum_const(u64::MAX)), tnum_const(u64::MAX - 1));
+}
+
+#[test]
+fn test_header_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/tnum_rs.h"));
+    let checked_in = include_str!("../../include/tnum_rs.h");
+    assert!(
+        generated == checked_in,
+        "include/tnum_rs.h is stale, run `TNUM_FFI_UPDATE_HEADER=1 cargo build -p tnum-ffi`"
+    );
+}
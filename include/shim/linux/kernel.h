@@ -0,0 +1,10 @@
+/* Stand-in for <linux/kernel.h> so src/tnum.c builds in user space.
+ * Everything tnum.c needs comes from tnum.h.
+ */
+#ifndef __SHIM_LINUX_KERNEL_H
+#define __SHIM_LINUX_KERNEL_H
+
+#include <stdint.h>
+#include <stdbool.h>
+
+#endif /* __SHIM_LINUX_KERNEL_H */
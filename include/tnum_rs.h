@@ -0,0 +1,223 @@
+/* Generated by cbindgen from ffi/src/lib.rs, do not edit */
+
+#ifndef __TNUM_RS_H
+#define __TNUM_RS_H
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "tnum.h"
+typedef struct tnum tnum_t;
+
+/**
+ * `OverflowFlag` 的 C 表示
+ */
+typedef enum TnumOverflow {
+  /**
+   * 所有取值都不会回绕
+   */
+  TNUM_OVERFLOW_NEVER,
+  /**
+   * 部分取值会回绕
+   */
+  TNUM_OVERFLOW_MAYBE,
+  /**
+   * 所有取值都会回绕
+   */
+  TNUM_OVERFLOW_ALWAYS,
+} TnumOverflow;
+
+/**
+ * `Endian` 的 C 表示
+ */
+typedef enum TnumEndian {
+  /**
+   * 小端
+   */
+  TNUM_ENDIAN_LITTLE,
+  /**
+   * 大端
+   */
+  TNUM_ENDIAN_BIG,
+} TnumEndian;
+
+/**
+ * 带溢出标志的运算结果
+ */
+typedef struct TnumOverflowing {
+  tnum_t result;
+  enum TnumOverflow overflow;
+} TnumOverflowing;
+
+/**
+ * 双倍宽度乘积的低半部分与高半部分
+ */
+typedef struct TnumWide {
+  tnum_t lo;
+  tnum_t hi;
+} TnumWide;
+
+/**
+ * 计数类运算的取值范围 [min, max]
+ */
+typedef struct TnumBounds {
+  uint32_t min;
+  uint32_t max;
+} TnumBounds;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+tnum_t rust_tnum_const(uint64_t value);
+
+tnum_t rust_tnum_range(uint64_t min, uint64_t max);
+
+tnum_t rust_tnum_range_wrapped(uint64_t min, uint64_t max);
+
+tnum_t rust_tnum_srange(int64_t min, int64_t max);
+
+tnum_t rust_tnum_const_subreg(tnum_t a, uint32_t value);
+
+tnum_t rust_tnum_lshift(tnum_t a, uint8_t shift);
+
+tnum_t rust_tnum_rshift(tnum_t a, uint8_t shift);
+
+tnum_t rust_tnum_arshift(tnum_t a, uint8_t min_shift, uint8_t insn_bitness);
+
+tnum_t rust_tnum_rotl(tnum_t a, uint8_t shift);
+
+tnum_t rust_tnum_rotr(tnum_t a, uint8_t shift);
+
+tnum_t rust_tnum_rotl_var(tnum_t a, tnum_t shift);
+
+tnum_t rust_tnum_rotr_var(tnum_t a, tnum_t shift);
+
+tnum_t rust_tnum_add(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_sub(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_neg(tnum_t a);
+
+tnum_t rust_tnum_abs(tnum_t a);
+
+struct TnumOverflowing rust_tnum_add_overflowing(tnum_t a, tnum_t b);
+
+struct TnumOverflowing rust_tnum_sub_overflowing(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_saturating_add(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_saturating_sub(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_div(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_mod(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_umin(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_umax(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_smin(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_smax(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_mul(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_mul_opt(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_mul_by_const(tnum_t a, uint64_t c);
+
+tnum_t rust_tnum_mul_rec(tnum_t a, tnum_t b);
+
+tnum_t rust_xtnum_mul_top(tnum_t a, tnum_t b);
+
+tnum_t rust_xtnum_mul_high_top(tnum_t a, tnum_t b);
+
+struct TnumOverflowing rust_tnum_mul_overflowing(tnum_t a, tnum_t b);
+
+struct TnumWide rust_tnum_mul_wide(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_mulhi(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_and(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_or(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_xor(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_popcount(tnum_t a);
+
+tnum_t rust_tnum_clz(tnum_t a);
+
+tnum_t rust_tnum_ctz(tnum_t a);
+
+struct TnumBounds rust_tnum_popcount_range(tnum_t a);
+
+struct TnumBounds rust_tnum_clz_range(tnum_t a);
+
+struct TnumBounds rust_tnum_ctz_range(tnum_t a);
+
+tnum_t rust_tnum_bswap16(tnum_t a);
+
+tnum_t rust_tnum_bswap32(tnum_t a);
+
+tnum_t rust_tnum_bswap64(tnum_t a);
+
+tnum_t rust_tnum_to_le(tnum_t a, uint8_t width, enum TnumEndian target);
+
+tnum_t rust_tnum_to_be(tnum_t a, uint8_t width, enum TnumEndian target);
+
+tnum_t rust_tnum_join(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_intersect(tnum_t a, tnum_t b);
+
+bool rust_tnum_in(tnum_t a, tnum_t b);
+
+bool rust_tnum_is_aligned(tnum_t a, uint64_t size);
+
+uint8_t rust_tnum_size(tnum_t a);
+
+tnum_t rust_tnum_cast(tnum_t a, uint8_t size);
+
+tnum_t rust_tnum_sext(tnum_t a, uint8_t from_bits);
+
+tnum_t rust_tnum_subreg(tnum_t a);
+
+tnum_t rust_tnum_clear_subreg(tnum_t a);
+
+tnum_t rust_tnum_with_subreg(tnum_t reg, tnum_t subreg);
+
+tnum_t rust_tnum_add_32(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_sub_32(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_mul_32(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_div_32(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_mod_32(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_and_32(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_or_32(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_xor_32(tnum_t a, tnum_t b);
+
+tnum_t rust_tnum_lshift_32(tnum_t a, uint8_t shift);
+
+tnum_t rust_tnum_rshift_32(tnum_t a, uint8_t shift);
+
+tnum_t rust_tnum_arshift_32(tnum_t a, uint8_t shift);
+
+tnum_t rust_tnum_rotl_32(tnum_t a, uint8_t shift);
+
+tnum_t rust_tnum_rotr_32(tnum_t a, uint8_t shift);
+
+tnum_t rust_tnum_rotl_var_32(tnum_t a, tnum_t shift);
+
+tnum_t rust_tnum_rotr_var_32(tnum_t a, tnum_t shift);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* __TNUM_RS_H */
@@ -1 +1,21 @@
-pub mod tnum;
\ No newline at end of file
+//! Tristate numbers (tnums), the bit-level abstract domain of the eBPF verifier
+//!
+//! The crate is `no_std`. Functions that build a `String` (`tnum_sbin`,
+//! `tnum_strn`) need the `alloc` feature; `Display`/`FromStr` and the
+//! `TnumStrn` adapter do not.
+
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod tnum;
+#[cfg(feature = "harness")]
+pub mod tnum_c;
+pub mod tnum_fmt;
+pub mod tnum_methods;
+#[cfg(feature = "rand")]
+pub mod tnum_rand;
+#[cfg(feature = "serde")]
+pub mod tnum_serde;
+pub mod tnum_word;
@@ -1,80 +1,262 @@
 //! This is a tnum implementation for Solana eBPF
+//!
+//! The domain is generic over the machine word ([TnumWord]), so the same
+//! transfer functions can be checked exhaustively at 8 bits and shipped at 64.
+
+pub use crate::tnum_word::{TnumWideWord, TnumWord};
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
 
 // This is for bit-level abstraction
 #[derive(Debug, Clone, Copy)]
-/// tnum definition
-pub struct Tnum {
-    value: u64,
-    mask: u64,
+#[repr(C)]
+/// tnum definition. `Tnum<u64>` has the layout of the C `struct tnum`
+pub struct Tnum<W = u64> {
+    value: W,
+    mask: W,
 }
 
-impl Tnum {
+impl<W: TnumWord> Tnum<W> {
+    /// 完全未知（格的顶元素）
+    pub const TOP: Self = Tnum { value: W::ZERO, mask: W::MAX };
+
+    /// 空集（格的底元素）。value 与 mask 有交集的 tnum 都表示空集，这里取全 1
+    pub const BOTTOM: Self = Tnum { value: W::MAX, mask: W::MAX };
+
     /// 创建实例
-    pub fn new(value: u64, mask: u64) -> Self {
+    pub fn new(value: W, mask: W) -> Self {
         Self { value, mask }
     }
+
+    /// 完全未知的 tnum，对应 C 版本的 tnum_unknown
+    pub fn unknown() -> Self {
+        Self::TOP
+    }
     
     /// 获取 value 字段
-    pub fn value(&self) -> u64 {
+    pub fn value(&self) -> W {
         self.value
     }
 
     /// 获取 mask 字段
-    pub fn mask(&self) -> u64 {
+    pub fn mask(&self) -> W {
         self.mask
     }
+
+    /// 所有位都已知
+    pub fn is_const(&self) -> bool {
+        self.mask == W::ZERO
+    }
+
+    /// 常数时返回其值
+    pub fn as_const(&self) -> Option<W> {
+        if self.is_const() {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+
+    /// 所有位都未知
+    pub fn is_top(&self) -> bool {
+        self.mask == W::MAX && self.value == W::ZERO
+    }
+
+    /// 同 is_top
+    pub fn is_unknown(&self) -> bool {
+        self.is_top()
+    }
+
+    /// 表示空集
+    pub fn is_bottom(&self) -> bool {
+        self.value & self.mask != W::ZERO
+    }
+
+    /// 已知位的位图
+    pub fn known_bits(&self) -> W {
+        !self.mask
+    }
+
+    /// 未知位的个数
+    pub fn unknown_count(&self) -> u32 {
+        self.mask.count_ones()
+    }
+
+    /// 符号位：已知时返回 Some(是否为负)，未知时返回 None
+    pub fn sign_bit(&self) -> Option<bool> {
+        if self.mask & sign_bit() != W::ZERO {
+            None
+        } else {
+            Some(self.value & sign_bit() != W::ZERO)
+        }
+    }
+
+    /// 一定是非负数
+    pub fn is_nonneg(&self) -> bool {
+        self.sign_bit() == Some(false)
+    }
+
+    /// 一定是负数
+    pub fn is_neg(&self) -> bool {
+        self.sign_bit() == Some(true)
+    }
+
+    /// 有符号解释下的最小值
+    pub fn smin(&self) -> W::Signed {
+        tnum_smin_value(*self).to_signed()
+    }
+
+    /// 有符号解释下的最大值
+    pub fn smax(&self) -> W::Signed {
+        tnum_smax_value(*self).to_signed()
+    }
+
+    /// 有符号比较：所有取值都小于（大于）other 的所有取值时返回 Less（Greater），
+    /// 两者是同一个常数时返回 Equal，其余情况无法确定，返回 None
+    pub fn scmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        if self.smax() < other.smin() {
+            Some(core::cmp::Ordering::Less)
+        } else if self.smin() > other.smax() {
+            Some(core::cmp::Ordering::Greater)
+        } else if self.mask == W::ZERO && other.mask == W::ZERO && self.value == other.value {
+            Some(core::cmp::Ordering::Equal)
+        } else {
+            None
+        }
+    }
+}
+
+/// 所有空集表示相等，其余按 value 与 mask 比较
+impl<W: TnumWord> PartialEq for Tnum<W> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.is_bottom() || other.is_bottom() {
+            self.is_bottom() && other.is_bottom()
+        } else {
+            self.value == other.value && self.mask == other.mask
+        }
+    }
+}
+
+impl<W: TnumWord> Eq for Tnum<W> {}
+
+/// 按集合包含关系的偏序：a <= b 当且仅当 a 表示的集合是 b 的子集
+impl<W: TnumWord> PartialOrd for Tnum<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        use core::cmp::Ordering;
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.is_bottom() || tnum_in(*other, *self) {
+            Some(Ordering::Less)
+        } else if other.is_bottom() || tnum_in(*self, *other) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
 }
 
 /// 创建一个常数 tnum 实例
-pub fn tnum_const(value: u64) -> Tnum {
-    Tnum::new(value, 0)
+pub fn tnum_const<W: TnumWord>(value: W) -> Tnum<W> {
+    Tnum::new(value, W::ZERO)
 }
 
-/// from integer interval to tnum
-pub fn tnum_range(min: u64, max: u64) -> Tnum {
+/// from integer interval to tnum (min <= max). The result is the best tnum:
+/// the common prefix of min and max is kept and every bit below it is unknown.
+pub fn tnum_range<W: TnumWord>(min: W, max: W) -> Tnum<W> {
     let chi = min ^ max;
     //最高未知位
-    let bits = (64 - chi.leading_zeros()) as u64;
-    //超出范围则完全未知
-    if bits > 63 {
-        return Tnum::new(0, u64::MAX);
+    let bits = W::BITS - chi.leading_zeros();
+    //范围内的未知位；bits 为字宽时所有位都未知，避免移位溢出
+    let delta = if bits == W::BITS {
+        W::MAX
+    } else {
+        (W::ONE << bits) - W::ONE
+    };
+    Tnum::new(min & !delta, delta)
+}
+
+/// 回绕区间的最优 tnum：min > max 时表示 [min, MAX] ∪ [0, max]
+pub fn tnum_range_wrapped<W: TnumWord>(min: W, max: W) -> Tnum<W> {
+    if min <= max {
+        tnum_range(min, max)
+    } else {
+        tnum_join(tnum_range(min, W::MAX), tnum_range(W::ZERO, max))
     }
+}
 
-    //范围内的未知位
-    let delta = (1u64 << bits) - 1;
-    Tnum::new(min & !delta, delta)
+/// from signed integer interval to tnum (min <= max)
+pub fn tnum_srange(min: i64, max: i64) -> Tnum {
+    tnum_range_wrapped(min as u64, max as u64)
 }
 
 /// tnum 的左移操作
-pub fn tnum_lshift(a: Tnum, shift: u8) -> Tnum {
-    Tnum::new(a.value << shift, a.mask << shift)
+pub fn tnum_lshift<W: TnumWord>(a: Tnum<W>, shift: u8) -> Tnum<W> {
+    Tnum::new(a.value << shift as u32, a.mask << shift as u32)
 }
 
 /// tnum 的右移操作
-pub fn tnum_rshift(a: Tnum, shift: u8) -> Tnum {
-    Tnum::new(a.value >> shift, a.mask >> shift)
+pub fn tnum_rshift<W: TnumWord>(a: Tnum<W>, shift: u8) -> Tnum<W> {
+    Tnum::new(a.value >> shift as u32, a.mask >> shift as u32)
 }
 
 /// tnum 算数右移的操作
-pub fn tnum_arshift(a: Tnum, min_shift: u8, insn_bitness: u8) -> Tnum {
-    match insn_bitness {
-        32 => {
-            //32位模式
-            let value = ((a.value as i32) >> min_shift) as u32;
-            let mask = ((a.mask as i32) >> min_shift) as u32;
-            Tnum::new(value as u64, mask as u64)
-        }
-        _ => {
-            //64位模式
-            let value = ((a.value as i64) >> min_shift) as u64;
-            let mask = ((a.mask as i64) >> min_shift) as u64;
-            Tnum::new(value, mask)
-        }
+pub fn tnum_arshift<W: TnumWord>(a: Tnum<W>, min_shift: u8, insn_bitness: u8) -> Tnum<W> {
+    // 32位模式只看低 32 位，并零扩展结果；其余情况按整个字处理
+    let pad = if insn_bitness == 32 && W::BITS > 32 {
+        W::BITS - 32
+    } else {
+        0
+    };
+    let value = (a.value << pad).arshift(min_shift as u32) >> pad;
+    let mask = (a.mask << pad).arshift(min_shift as u32) >> pad;
+    Tnum::new(value, mask)
+}
+
+/// tnum 的循环左移操作，移位量按字宽取模
+pub fn tnum_rotl<W: TnumWord>(a: Tnum<W>, shift: u8) -> Tnum<W> {
+    let k = shift as u32 % W::BITS;
+    if k == 0 {
+        return a;
     }
+    Tnum::new(
+        (a.value << k) | (a.value >> (W::BITS - k)),
+        (a.mask << k) | (a.mask >> (W::BITS - k)),
+    )
+}
+
+/// tnum 的循环右移操作，移位量按字宽取模
+pub fn tnum_rotr<W: TnumWord>(a: Tnum<W>, shift: u8) -> Tnum<W> {
+    let k = shift as u32 % W::BITS;
+    tnum_rotl(a, (W::BITS - k) as u8)
+}
+
+/// 移位量为 tnum 时的循环左移：对所有可能的移位量取 join
+pub fn tnum_rotl_var<W: TnumWord>(a: Tnum<W>, shift: Tnum<W>) -> Tnum<W> {
+    tnum_rot_var(a, shift, tnum_rotl)
+}
+
+/// 移位量为 tnum 时的循环右移：对所有可能的移位量取 join
+pub fn tnum_rotr_var<W: TnumWord>(a: Tnum<W>, shift: Tnum<W>) -> Tnum<W> {
+    tnum_rot_var(a, shift, tnum_rotr)
+}
+
+/// 移位量为空集时没有可能的结果，返回 BOTTOM
+fn tnum_rot_var<W: TnumWord>(a: Tnum<W>, shift: Tnum<W>, rot: fn(Tnum<W>, u8) -> Tnum<W>) -> Tnum<W> {
+    // tnum_and 会把空集变成常数，需要先判断
+    if shift.is_bottom() {
+        return Tnum::BOTTOM;
+    }
+    // 只有低 log2(BITS) 位决定实际的移位量
+    let shift = tnum_and(shift, tnum_const(W::from_u128(W::BITS as u128 - 1)));
+    (0..W::BITS)
+        .filter(|&k| tnum_in(shift, tnum_const(W::from_u128(k as u128))))
+        .map(|k| rot(a, k as u8))
+        .reduce(tnum_join)
+        .unwrap_or(Tnum::BOTTOM)
 }
 
 /// tnum 的加法操作
-pub fn tnum_add(a: Tnum, b: Tnum) -> Tnum {
+pub fn tnum_add<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
     // 计算掩码之和 - 表示两个不确定数的掩码组合
     let sm = a.mask + b.mask;
 
@@ -102,7 +284,7 @@ pub fn tnum_add(a: Tnum, b: Tnum) -> Tnum {
 }
 
 /// tnum 的减法操作
-pub fn tnum_sub(a: Tnum, b: Tnum) -> Tnum {
+pub fn tnum_sub<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
     let dv = a.value - b.value;
     let alpha = dv + a.mask;
     let beta = dv - b.mask;
@@ -111,8 +293,28 @@ pub fn tnum_sub(a: Tnum, b: Tnum) -> Tnum {
     Tnum::new(dv & !mu, mu)
 }
 
+/// tnum 的取负操作（BPF_NEG）
+pub fn tnum_neg<W: TnumWord>(a: Tnum<W>) -> Tnum<W> {
+    tnum_sub_wrapping(tnum_const(W::ZERO), a)
+}
+
+/// tnum 的有符号绝对值，最小负数的绝对值回绕为其自身
+pub fn tnum_abs<W: TnumWord>(a: Tnum<W>) -> Tnum<W> {
+    match a.sign_bit() {
+        Some(false) => a,
+        Some(true) => tnum_neg(a),
+        None => {
+            // 分别按符号位为 0 / 1 计算后取 join
+            let rest = a.mask & !sign_bit::<W>();
+            let pos = Tnum::new(a.value, rest);
+            let neg = Tnum::new(a.value | sign_bit(), rest);
+            tnum_join(pos, tnum_neg(neg))
+        }
+    }
+}
+
 /// tnum 的按位与操作
-pub fn tnum_and(a: Tnum, b: Tnum) -> Tnum {
+pub fn tnum_and<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
     let alpha = a.value | a.mask;
     let beta = b.value | b.mask;
     let v = a.value & b.value;
@@ -121,7 +323,7 @@ pub fn tnum_and(a: Tnum, b: Tnum) -> Tnum {
 }
 
 /// tnum 的按位或操作
-pub fn tnum_or(a: Tnum, b: Tnum) -> Tnum {
+pub fn tnum_or<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
     let v = a.value | b.value;
     let mu = a.mask | b.mask;
 
@@ -129,79 +331,385 @@ pub fn tnum_or(a: Tnum, b: Tnum) -> Tnum {
 }
 
 /// tnum 的按位异或操作
-pub fn tnum_xor(a: Tnum, b: Tnum) -> Tnum {
+pub fn tnum_xor<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
     let v = a.value ^ b.value;
     let mu = a.mask | b.mask;
 
     Tnum::new(v & !mu, mu)
 }
 
-/// tnum 的乘法操作
-pub fn tnum_mul(mut a: Tnum, mut b: Tnum) -> Tnum {
-    let acc_v = a.value * b.value;
-    let mut acc_m: Tnum = Tnum::new(0, 0);
-    while (a.value != 0) || (a.mask != 0) {
-        if (a.value & 1) != 0 {
-            acc_m = tnum_add(acc_m, Tnum::new(0, b.mask));
-        } else if (a.mask & 1) != 0 {
-            acc_m = tnum_add(acc_m, Tnum::new(0, b.value | b.mask));
+/// tnum 的乘法操作，按字宽回绕，与内核 C 版本的 tnum_mul 行为一致
+pub fn tnum_mul<W: TnumWord>(mut a: Tnum<W>, mut b: Tnum<W>) -> Tnum<W> {
+    let acc_v = a.value.wrapping_mul(b.value);
+    let mut acc_m: Tnum<W> = Tnum::new(W::ZERO, W::ZERO);
+    while (a.value != W::ZERO) || (a.mask != W::ZERO) {
+        if (a.value & W::ONE) != W::ZERO {
+            acc_m = tnum_add_wrapping(acc_m, Tnum::new(W::ZERO, b.mask));
+        } else if (a.mask & W::ONE) != W::ZERO {
+            acc_m = tnum_add_wrapping(acc_m, Tnum::new(W::ZERO, b.value | b.mask));
         }
         a = tnum_rshift(a, 1);
         b = tnum_lshift(b, 1);
     }
-    tnum_add(Tnum::new(acc_v, 0), acc_m)
+    tnum_add_wrapping(Tnum::new(acc_v, W::ZERO), acc_m)
 }
 
 /// A constant-value optimization for tnum_mul
-pub fn tnum_mul_opt(a: Tnum, b: Tnum) -> Tnum {
+pub fn tnum_mul_opt<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
     // 如果一个是常数
-    if a.mask == 0 && a.value.count_ones() == 1 { // a.value = 2 ^ x
+    if a.mask == W::ZERO && a.value.count_ones() == 1 { // a.value = 2 ^ x
         tnum_lshift(b, a.value.trailing_zeros() as u8)
-    } else if b.mask == 0  && b.value.count_ones() == 1 { // a.value = 2 ^ x
+    } else if b.mask == W::ZERO  && b.value.count_ones() == 1 { // a.value = 2 ^ x
         tnum_lshift(a, b.value.trailing_zeros() as u8)
-    } else 
-        if (a.value | a.mask).count_ones() <= (b.value | b.mask).count_ones() {
+    } else if a.mask == W::ZERO { // a 为任意常数
+        tnum_mul_by_const(b, a.value)
+    } else if b.mask == W::ZERO {
+        tnum_mul_by_const(a, b.value)
+    } else if (a.value | a.mask).count_ones() <= (b.value | b.mask).count_ones() {
         tnum_mul(a, b)
     } else {
         tnum_mul(b, a)
     }
 }
 
+/// 不会溢出 panic 的 tnum 加法，与内核 C 版本的 tnum_add 行为一致
+pub fn tnum_add_wrapping<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    let sm = a.mask.wrapping_add(b.mask);
+    let sv = a.value.wrapping_add(b.value);
+    let sigma = sm.wrapping_add(sv);
+    let chi = sigma ^ sv;
+    let mu = chi | a.mask | b.mask;
+    Tnum::new(sv & !mu, mu)
+}
+
+/// 不会溢出 panic 的 tnum 减法，与内核 C 版本的 tnum_sub 行为一致
+pub fn tnum_sub_wrapping<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    let dv = a.value.wrapping_sub(b.value);
+    let alpha = dv.wrapping_add(a.mask);
+    let beta = dv.wrapping_sub(b.mask);
+    let chi = alpha ^ beta;
+    let mu = chi | a.mask | b.mask;
+    Tnum::new(dv & !mu, mu)
+}
+
+/// [tnum_naf] computes the non-adjacent form of [c] as two bitmaps
+/// (positive digits, negative digits) such that c = pos - neg (mod 2^BITS).
+fn tnum_naf<W: TnumWord>(c: W) -> (W, W) {
+    let ch = c >> 1;
+    let c3 = c.wrapping_add(ch);
+    let diff = ch ^ c3;
+    (c3 & diff, ch & diff)
+}
+
+/// sum of the partial products (0, mask << k) for every bit k set in [digits]
+fn tnum_mask_sum<W: TnumWord>(mask: W, mut digits: W) -> Tnum<W> {
+    let mut acc = Tnum::new(W::ZERO, W::ZERO);
+    while digits != W::ZERO {
+        let k = digits.trailing_zeros();
+        acc = tnum_add_wrapping(acc, Tnum::new(W::ZERO, mask << k));
+        digits = digits & (digits - W::ONE);
+    }
+    acc
+}
+
+/// [tnum_mul_by_const] multiplies the tnum [a] by the constant [c].
+/// [c] is recoded in canonical signed digit (NAF) form so that runs of ones
+/// cost one addition and one subtraction of shifted partial products.
+/// The result is intersected with both operand orders of the plain
+/// shift-and-add product, so it is never less precise than [tnum_mul].
+pub fn tnum_mul_by_const<W: TnumWord>(a: Tnum<W>, c: W) -> Tnum<W> {
+    let acc_v = Tnum::new(c.wrapping_mul(a.value), W::ZERO);
+    if a.mask == W::ZERO || c == W::ZERO {
+        return acc_v;
+    }
+    let (pos, neg) = tnum_naf(c);
+    let csd = tnum_sub_wrapping(
+        tnum_add_wrapping(acc_v, tnum_mask_sum(a.mask, pos)),
+        tnum_mask_sum(a.mask, neg),
+    );
+    // tnum_mul(tnum_const(c), a) 与 tnum_mul(a, tnum_const(c))
+    let by_c = tnum_add_wrapping(acc_v, tnum_mask_sum(a.mask, c));
+    let by_a = tnum_add_wrapping(acc_v, tnum_mask_sum(c, a.mask));
+    tnum_intersect(tnum_intersect(csd, by_c), by_a)
+}
+
+/// 零扩展到更宽（或截断到更窄）的字
+fn tnum_resize<W: TnumWord, V: TnumWord>(a: Tnum<W>) -> Tnum<V> {
+    Tnum::new(V::from_u128(a.value.to_u128()), V::from_u128(a.mask.to_u128()))
+}
+
+/// [tnum_mul_wide] computes the full double-width product of [a] and [b]
+/// and returns it as (low half, high half). It uses the same shift-and-add
+/// structure as [tnum_mul] with [W::Wide] accumulators, so [W] must have a
+/// double-width word ([TnumWideWord]); u128 has none and is rejected at
+/// compile time.
+pub fn tnum_mul_wide<W: TnumWideWord>(a: Tnum<W>, b: Tnum<W>) -> (Tnum<W>, Tnum<W>) {
+    // 双倍宽度的乘积不会超过 W::Wide，回绕加法在这里是精确的
+    let p: Tnum<W::Wide> = tnum_mul(tnum_resize(a), tnum_resize(b));
+    (tnum_resize(p), tnum_resize(tnum_rshift(p, W::BITS as u8)))
+}
+
+/// 乘积的高半部分
+pub fn tnum_mulhi<W: TnumWideWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    tnum_mul_wide(a, b).1
+}
+
+/// 无符号运算是否可能回绕
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowFlag {
+    /// 所有取值都不会回绕
+    Never,
+    /// 部分取值会回绕
+    Maybe,
+    /// 所有取值都会回绕
+    Always,
+}
+
+impl OverflowFlag {
+    /// 由最小、最大取值是否回绕得到标志
+    fn from_bounds(min_wraps: bool, max_wraps: bool) -> Self {
+        if min_wraps {
+            OverflowFlag::Always
+        } else if max_wraps {
+            OverflowFlag::Maybe
+        } else {
+            OverflowFlag::Never
+        }
+    }
+}
+
+/// 回绕的 tnum 加法，并报告无符号加法是否可能溢出
+pub fn tnum_add_overflowing<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> (Tnum<W>, OverflowFlag) {
+    let flag = OverflowFlag::from_bounds(
+        a.value.checked_add(b.value).is_none(),
+        tnum_max(a).checked_add(tnum_max(b)).is_none(),
+    );
+    (tnum_add_wrapping(a, b), flag)
+}
+
+/// 回绕的 tnum 减法，并报告无符号减法是否可能借位
+pub fn tnum_sub_overflowing<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> (Tnum<W>, OverflowFlag) {
+    // 被减数取最大、减数取最小时最不容易借位
+    let flag = OverflowFlag::from_bounds(tnum_max(a) < b.value, a.value < tnum_max(b));
+    (tnum_sub_wrapping(a, b), flag)
+}
+
+/// 回绕的 tnum 乘法，并报告无符号乘法是否可能溢出
+pub fn tnum_mul_overflowing<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> (Tnum<W>, OverflowFlag) {
+    let flag = OverflowFlag::from_bounds(
+        a.value.checked_mul(b.value).is_none(),
+        tnum_max(a).checked_mul(tnum_max(b)).is_none(),
+    );
+    (tnum_mul(a, b), flag)
+}
+
+/// tnum 的无符号除法操作，除数为 0 时结果为 0（eBPF 语义）
+pub fn tnum_div<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    if a.mask == W::ZERO && b.mask == W::ZERO {
+        return tnum_const(a.value.checked_div(b.value).unwrap_or(W::ZERO));
+    }
+    if b.mask == W::ZERO && b.value.is_power_of_two() {
+        return tnum_rshift(a, b.value.trailing_zeros() as u8);
+    }
+    let b_max = tnum_max(b);
+    if b_max == W::ZERO {
+        return tnum_const(W::ZERO);
+    }
+    // 除数的最小非零取值
+    let b_min = if b.value != W::ZERO {
+        b.value
+    } else {
+        W::ONE << b.mask.trailing_zeros()
+    };
+    let q = tnum_range(a.value / b_max, tnum_max(a) / b_min);
+    if b.value == W::ZERO {
+        tnum_join(q, tnum_const(W::ZERO))
+    } else {
+        q
+    }
+}
+
+/// tnum 的无符号取模操作，除数为 0 时结果为被除数（eBPF 语义）
+pub fn tnum_mod<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    if a.mask == W::ZERO && b.mask == W::ZERO {
+        return tnum_const(a.value.checked_rem(b.value).unwrap_or(a.value));
+    }
+    if b.mask == W::ZERO && b.value.is_power_of_two() {
+        return tnum_and(a, tnum_const(b.value - W::ONE));
+    }
+    let b_max = tnum_max(b);
+    if b_max == W::ZERO || tnum_max(a) < b.value {
+        return a;
+    }
+    let r = tnum_range(W::ZERO, core::cmp::min(tnum_max(a), b_max - W::ONE));
+    if b.value == W::ZERO {
+        tnum_join(r, a)
+    } else {
+        r
+    }
+}
+
+/// 无符号 min
+pub fn tnum_umin<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    if tnum_max(a) <= b.value {
+        a
+    } else if tnum_max(b) <= a.value {
+        b
+    } else {
+        let bound = tnum_range(
+            core::cmp::min(a.value, b.value),
+            core::cmp::min(tnum_max(a), tnum_max(b)),
+        );
+        tnum_intersect(tnum_join(a, b), bound)
+    }
+}
+
+/// 无符号 max
+pub fn tnum_umax<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    if tnum_max(a) <= b.value {
+        b
+    } else if tnum_max(b) <= a.value {
+        a
+    } else {
+        let bound = tnum_range(
+            core::cmp::max(a.value, b.value),
+            core::cmp::max(tnum_max(a), tnum_max(b)),
+        );
+        tnum_intersect(tnum_join(a, b), bound)
+    }
+}
+
+/// 有符号 min
+pub fn tnum_smin<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    let (a_min, a_max) = (signed_key(tnum_smin_value(a)), signed_key(tnum_smax_value(a)));
+    let (b_min, b_max) = (signed_key(tnum_smin_value(b)), signed_key(tnum_smax_value(b)));
+    if a_max <= b_min {
+        a
+    } else if b_max <= a_min {
+        b
+    } else {
+        let bound = tnum_range_wrapped(
+            signed_key(core::cmp::min(a_min, b_min)),
+            signed_key(core::cmp::min(a_max, b_max)),
+        );
+        tnum_intersect(tnum_join(a, b), bound)
+    }
+}
+
+/// 有符号 max
+pub fn tnum_smax<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    let (a_min, a_max) = (signed_key(tnum_smin_value(a)), signed_key(tnum_smax_value(a)));
+    let (b_min, b_max) = (signed_key(tnum_smin_value(b)), signed_key(tnum_smax_value(b)));
+    if a_max <= b_min {
+        b
+    } else if b_max <= a_min {
+        a
+    } else {
+        let bound = tnum_range_wrapped(
+            signed_key(core::cmp::max(a_min, b_min)),
+            signed_key(core::cmp::max(a_max, b_max)),
+        );
+        tnum_intersect(tnum_join(a, b), bound)
+    }
+}
+
+/// 无符号饱和加法
+pub fn tnum_saturating_add<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    let sum = tnum_add_wrapping(a, b);
+    let lo = a.value.wrapping_add(b.value);
+    if lo < a.value {
+        // 一定溢出
+        tnum_const(W::MAX)
+    } else if tnum_max(a) <= W::MAX - tnum_max(b) {
+        // 一定不溢出
+        sum
+    } else {
+        tnum_join(tnum_intersect(sum, tnum_range(lo, W::MAX)), tnum_const(W::MAX))
+    }
+}
+
+/// 无符号饱和减法
+pub fn tnum_saturating_sub<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    let diff = tnum_sub_wrapping(a, b);
+    if tnum_max(a) < b.value {
+        // 一定下溢
+        tnum_const(W::ZERO)
+    } else if a.value >= tnum_max(b) {
+        // 一定不下溢
+        diff
+    } else {
+        let hi = tnum_max(a) - b.value;
+        tnum_join(tnum_intersect(diff, tnum_range(W::ZERO, hi)), tnum_const(W::ZERO))
+    }
+}
+
 #[test]
-fn test_tnum_mul () -> (){
-    let a = Tnum::new(0b100, 0b011);
-    let b = Tnum::new(0b111, 0b000);
+fn test_tnum_mul () {
+    let a: Tnum = Tnum::new(0b100, 0b011);
+    let b: Tnum = Tnum::new(0b111, 0b000);
     println!("{:?}", tnum_mul(a, b));
     println!("{:?}", tnum_mul_opt(a, b));
 }
 
+#[test]
+fn test_tnum_mul_by_const() {
+    // 6 位以内穷举：结果必须包含所有具体乘积，且不比 tnum_mul 更不精确
+    for c in 0..64u64 {
+        for value in 0..64u64 {
+            for mask in 0..64u64 {
+                if value & mask != 0 {
+                    continue;
+                }
+                let a = Tnum::new(value, mask);
+                let r = tnum_mul_by_const(a, c);
+                let mut sub = mask;
+                loop {
+                    assert!(tnum_in(r, tnum_const((value | sub) * c)));
+                    if sub == 0 {
+                        break;
+                    }
+                    sub = (sub - 1) & mask;
+                }
+                assert!(tnum_in(tnum_mul(tnum_const(c), a), r));
+                assert!(tnum_in(tnum_mul(a, tnum_const(c)), r));
+            }
+        }
+    }
+    // 24 = 0b11000, 常见的数组下标步长
+    let r = tnum_mul_by_const(Tnum::new(0, 0b11), 24u64);
+    assert_eq!((r.value(), r.mask()), (0, 0b1111000));
+}
+
 
 ///computes the join of the tnum domain.
-pub fn tnum_join (a: Tnum, b: Tnum) -> Tnum {
+pub fn tnum_join<W: TnumWord> (a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
     let v = a.value ^ b.value;
     let m = (a.mask | b.mask) | v;
         Tnum::new((a.value | b.value) & (!m), m)
 }
 
-/// [split_at_mu] splits a tnum at the first unknow.
-fn split_at_mu (x:Tnum) -> (Tnum, u32 , Tnum) {
-    let i = x.mask.leading_ones();
-    let x1 = Tnum::new(x.value >> (i+1), x.mask >> (i+1));
-    let x2 = Tnum::new(x.value & ((1 << i) - 1), x.mask & ((1 << i) - 1));
+/// [split_at_mu] splits a tnum at its lowest unknown bit [i]:
+/// x = x1.mu.x2 where [x2] holds the i known bits below it.
+/// [x] must have an unknown bit.
+fn split_at_mu<W: TnumWord> (x:Tnum<W>) -> (Tnum<W>, u32 , Tnum<W>) {
+    let i = x.mask.trailing_zeros();
+    // 分两次移位：i 可以是最高位
+    let x1 = Tnum::new((x.value >> i) >> 1, (x.mask >> i) >> 1);
+    let x2 = Tnum::new(x.value & ((W::ONE << i) - W::ONE), x.mask & ((W::ONE << i) - W::ONE));
         (x1,i,x2)
 }
 
 /// [tnum_mul_const] multiplies a constant [c] by the tnum [x]
 /// which has [j] unknown bits and [n] is the fuel (Z.of_nat n = j).
-fn tnum_mul_const (c:u64, x:Tnum, n:u64) -> Tnum {
+fn tnum_mul_const<W: TnumWord> (c:W, x:Tnum<W>, n:u64) -> Tnum<W> {
     if n == 0 {
-        Tnum::new(c * x.value, 0)
+        Tnum::new(c.wrapping_mul(x.value), W::ZERO)
     } else {
         let (y1,i1,y2) = split_at_mu(x);
         let p = tnum_mul_const(c,y1,n-1);
-        let mc = Tnum::new(c * y2.mask,0);
-        let mu0 = tnum_add(tnum_lshift(p, (i1+1) as u8), mc);
-        let mu1 = tnum_add(mu0, Tnum::new(c<<i1,0));
+        let mc = Tnum::new(c.wrapping_mul(y2.value),W::ZERO);
+        let mu0 = tnum_add_wrapping(tnum_lshift(tnum_lshift(p, i1 as u8), 1), mc);
+        let mu1 = tnum_add_wrapping(mu0, Tnum::new(c<<i1,W::ZERO));
            tnum_join(mu0, mu1)
     }
 
@@ -210,9 +718,9 @@ fn tnum_mul_const (c:u64, x:Tnum, n:u64) -> Tnum {
 /// [xtnum_mul x i y j] computes the multiplication of
 /// [x]  which has [i] unknown bits by
 /// [y]  which has [j] unknown bits such (i <= j)
-fn xtnum_mul (x:Tnum, i: u64, y:Tnum, j: u64) -> Tnum {
+fn xtnum_mul<W: TnumWord> (x:Tnum<W>, i: u64, y:Tnum<W>, j: u64) -> Tnum<W> {
     if i == 0 && j == 0 {
-        Tnum::new(x.value * y.value, 0)
+        Tnum::new(x.value.wrapping_mul(y.value), W::ZERO)
     } else {
         let (y1,i1,y2) = split_at_mu(y); // y = y1.mu.y2
         let p = if i == j {
@@ -221,16 +729,16 @@ fn xtnum_mul (x:Tnum, i: u64, y:Tnum, j: u64) -> Tnum {
             xtnum_mul(x, i, y1, j-1)
         };
         let mc = tnum_mul_const(y2.value, x, i);
-        let mu0 = tnum_add(tnum_lshift(p, (i1+1) as u8), mc);
-        let mu1 = tnum_add(mu0, tnum_lshift(x, i1 as u8));
+        let mu0 = tnum_add_wrapping(tnum_lshift(tnum_lshift(p, i1 as u8), 1), mc);
+        let mu1 = tnum_add_wrapping(mu0, tnum_lshift(x, i1 as u8));
             tnum_join(mu0, mu1)
     }
 }
 
 /// the top of the xtnum_mul
-pub fn xtnum_mul_top (x:Tnum, y:Tnum) -> Tnum {
-    let i = 64 - x.mask.leading_zeros() as u64;
-    let j = 64 - y.mask.leading_zeros() as u64;
+pub fn xtnum_mul_top<W: TnumWord> (x:Tnum<W>, y:Tnum<W>) -> Tnum<W> {
+    let i = x.mask.count_ones() as u64;
+    let j = y.mask.count_ones() as u64;
         if i <= j {
             xtnum_mul(x, i, y, j)
         } else {
@@ -239,49 +747,102 @@ pub fn xtnum_mul_top (x:Tnum, y:Tnum) -> Tnum {
 }
 
 /// clear bit of n-th
-fn clear_bit(num: u64, pos: u8) -> u64 {
-    num & !(1 << pos)
+fn clear_bit<W: TnumWord>(num: W, pos: u32) -> W {
+    num & !(W::ONE << pos)
 }
 
 /// clear bit of a tnum
-fn tnum_clearbit(x: Tnum, pos: u8) -> Tnum {
+fn tnum_clearbit<W: TnumWord>(x: Tnum<W>, pos: u32) -> Tnum<W> {
     Tnum::new(clear_bit(x.value, pos), clear_bit(x.mask, pos))
 }
 
-/// bit size of a tnum
-fn tnum_size (x: Tnum) -> u8 {
-    let a = 64 - x.value.leading_zeros();
-    let b = 64 - x.mask.leading_zeros();
-    if a < b {
-        b as u8
-    } else {
-        a as u8
-    }
+/// bit size of a tnum, i.e. the position of its highest possibly-set bit
+pub fn tnum_size<W: TnumWord> (x: Tnum<W>) -> u32 {
+    let a = W::BITS - x.value.leading_zeros();
+    let b = W::BITS - x.mask.leading_zeros();
+    core::cmp::max(a, b)
+}
+
+/// [min, max] of the number of set bits (popcount)
+pub fn tnum_popcount_range<W: TnumWord>(a: Tnum<W>) -> (u32, u32) {
+    (a.value.count_ones(), (a.value | a.mask).count_ones())
+}
+
+/// [min, max] of the number of leading zeros (clz)
+pub fn tnum_clz_range<W: TnumWord>(a: Tnum<W>) -> (u32, u32) {
+    (W::BITS - tnum_size(a), a.value.leading_zeros())
+}
+
+/// [min, max] of the number of trailing zeros (ctz)
+pub fn tnum_ctz_range<W: TnumWord>(a: Tnum<W>) -> (u32, u32) {
+    ((a.value | a.mask).trailing_zeros(), a.value.trailing_zeros())
+}
+
+/// popcount 的 tnum 结果
+pub fn tnum_popcount<W: TnumWord>(a: Tnum<W>) -> Tnum<W> {
+    tnum_count(tnum_popcount_range(a))
+}
+
+/// clz 的 tnum 结果
+pub fn tnum_clz<W: TnumWord>(a: Tnum<W>) -> Tnum<W> {
+    tnum_count(tnum_clz_range(a))
+}
+
+/// ctz 的 tnum 结果
+pub fn tnum_ctz<W: TnumWord>(a: Tnum<W>) -> Tnum<W> {
+    tnum_count(tnum_ctz_range(a))
+}
+
+/// 把位计数的区间转换为 tnum
+fn tnum_count<W: TnumWord>((min, max): (u32, u32)) -> Tnum<W> {
+    tnum_range(W::from_u128(min as u128), W::from_u128(max as u128))
 }
 
-/// max 64 of a tnum
-fn tnum_max (a: Tnum) -> u64 {
+/// max value of a tnum
+fn tnum_max<W: TnumWord> (a: Tnum<W>) -> W {
     a.value | a.mask
 }
 
+/// 符号位
+fn sign_bit<W: TnumWord>() -> W {
+    W::ONE << (W::BITS - 1)
+}
+
+/// signed min value of a tnum: the sign bit set if unknown, other unknowns cleared
+fn tnum_smin_value<W: TnumWord>(a: Tnum<W>) -> W {
+    a.value | (a.mask & sign_bit())
+}
+
+/// signed max value of a tnum: the sign bit cleared if unknown, other unknowns set
+fn tnum_smax_value<W: TnumWord>(a: Tnum<W>) -> W {
+    (a.value | a.mask) & !(a.mask & sign_bit())
+}
+
+/// 把有符号数映射为保序的无符号数
+fn signed_key<W: TnumWord>(x: W) -> W {
+    x ^ sign_bit()
+}
+
 /// check if the pos-th of num is 0 or 1
-fn testbit(num: u64, pos: u8) -> bool {
-    if pos >= 64 {
+fn testbit<W: TnumWord>(num: W, pos: u32) -> bool {
+    if pos >= W::BITS {
         false
     } else {
-        (num & (1 << pos)) != 0
+        (num & (W::ONE << pos)) != W::ZERO
     }
 }
 
 /// [xtnum_mul_high x y n] multiplies x by y
 /// where n is the number of bits that are set in either x or y.
 /// We also have that x <= y and 0 <= x and 0 <= y
-fn xtnum_mul_high (x: Tnum, y: Tnum, n: u8) -> Tnum {
-    if x.mask == 0 && y.mask == 0 { //if both are constants, perform normal multiplication
-        Tnum::new(x.value * y.value, 0)
+fn xtnum_mul_high<W: TnumWord> (x: Tnum<W>, y: Tnum<W>, n: u32) -> Tnum<W> {
+    if x.mask == W::ZERO && y.mask == W::ZERO { //if both are constants, perform normal multiplication
+        Tnum::new(x.value.wrapping_mul(y.value), W::ZERO)
+    } else if y.value == W::ZERO && y.mask == W::ZERO { // y 为常数 0 时没有最高位可拆
+        Tnum::new(W::ZERO, W::ZERO)
     } else if n == 0 {
         //panic!("should not happen");
-        Tnum::new(0, 0) //should not happen
+        Tnum::TOP //should not happen, stay sound
     } else {
         let b = tnum_size(y);
         let ym = testbit(y.mask, b-1);
@@ -293,22 +854,23 @@ fn xtnum_mul_high (x: Tnum, y: Tnum, n: u8) -> Tnum {
                 xtnum_mul_high(x, y_prime, n-1)
             };
             if ym {
-                tnum_join(tnum_add(p,tnum_lshift(x, b-1)), p)
+                tnum_join(tnum_add_wrapping(p,tnum_lshift(x, (b-1) as u8)), p)
             } else {
-                tnum_add(p, tnum_lshift(x, b-1))
+                tnum_add_wrapping(p, tnum_lshift(x, (b-1) as u8))
             }
     }
 }
 
 /// the top level of xtnum_mul_high
-pub fn xtnum_mul_high_top (x: Tnum, y: Tnum) -> Tnum {
-    xtnum_mul_high(x, y,((x.value | x.mask).count_ones() + (y.value | y.mask).count_ones()) as u8)
+pub fn xtnum_mul_high_top<W: TnumWord> (x: Tnum<W>, y: Tnum<W>) -> Tnum<W> {
+    // u128 时可达 256，不能用 u8
+    xtnum_mul_high(x, y, (x.value | x.mask).count_ones() + (y.value | y.mask).count_ones())
 }
 
 #[test]
-fn test_xtnum_mul () -> (){
-    let a = Tnum::new(15, 0); // 2^4 - 1
-    let b = Tnum::new(0, 31); // 2^5 - 1
+fn test_xtnum_mul () {
+    let a: Tnum = Tnum::new(15, 0); // 2^4 - 1
+    let b: Tnum = Tnum::new(0, 31); // 2^5 - 1
     println!("{:?}", tnum_mul(a, b)); // Output: Tnum { value: 0, mask: 511 } 2^(4+5) -1
     println!("{:?}", xtnum_mul_top(a, b)); // Output: Tnum { value: 0, mask: 4095 }
     println!("{:?}", xtnum_mul_high_top(a, b)); // Tnum { value: 0, mask: 511 }
@@ -316,28 +878,28 @@ fn test_xtnum_mul () -> (){
 
 
 /// aux function for tnum_mul_rec
-fn tnum_decompose (a: Tnum) -> (Tnum, Tnum) {
+fn tnum_decompose<W: TnumWord> (a: Tnum<W>) -> (Tnum<W>, Tnum<W>) {
     (
         Tnum::new(a.value >> 1, a.mask >> 1),
-        Tnum::new(a.value & 1, a.mask & 1)
+        Tnum::new(a.value & W::ONE, a.mask & W::ONE)
     )
 }
 
 /// A new tnum_mul proposed by frederic
-pub fn tnum_mul_rec(a: Tnum, b: Tnum) -> Tnum {
-    if a.mask == 0 && b.mask == 0 {  // both are known
-        Tnum::new(a.value * b.value, 0)
-    } else if a.mask == u64::MAX && b.mask == u64::MAX { //both are unknown
-        Tnum::new(0,u64::MAX)
-    } else if (a.value == 0 && a.mask == 0) || (b.value == 0 && b.mask == 0) { // mult by 0
-        Tnum::new(0, 0)
-    } else if a.value == 1 && a.mask == 0 { // mult by 1
+pub fn tnum_mul_rec<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
+    if a.is_const() && b.is_const() {  // both are known
+        tnum_const(a.value.wrapping_mul(b.value))
+    } else if a.is_top() && b.is_top() { //both are unknown
+        Tnum::TOP
+    } else if (a.value == W::ZERO && a.mask == W::ZERO) || (b.value == W::ZERO && b.mask == W::ZERO) { // mult by 0
+        Tnum::new(W::ZERO, W::ZERO)
+    } else if a.value == W::ONE && a.mask == W::ZERO { // mult by 1
         b
-    } else if b.value == 1 && b.mask == 0 { // mult by 1
+    } else if b.value == W::ONE && b.mask == W::ZERO { // mult by 1
         a
     } else {
-        let (a_up,a_low) = tnum_decompose(a);
-        let (b_up,b_low) = tnum_decompose(b);
+        let (a_up,_a_low) = tnum_decompose(a);
+        let (b_up,_b_low) = tnum_decompose(b);
         tnum_mul_rec(a_up, b_up)
         //tnum_mul_rec(a_up, b_up) + tnum_mul_rec(a_up, b_low) + tnum_mul_rec(a_low, b_up) + tnum_mul_rec(a_low, b_low)
         // TODO: this one is wrong, replace this line with the following impl
@@ -356,25 +918,42 @@ pub fn tnum_mul_rec(a: Tnum, b: Tnum) -> Tnum {
 }
 
 /// tnum 的交集计算
-pub fn tnum_intersect(a: Tnum, b: Tnum) -> Tnum {
+pub fn tnum_intersect<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> Tnum<W> {
     let v = a.value | b.value;
     let mu = a.mask & b.mask;
     Tnum::new(v & !mu, mu)
 }
 
 /// tnum 用与截断到指定字节大小
-pub fn tnum_cast(mut a: Tnum, size: u8) -> Tnum {
+pub fn tnum_cast<W: TnumWord>(mut a: Tnum<W>, size: u8) -> Tnum<W> {
+    let bits = size as u32 * 8;
+    //不小于字宽时无需截断
+    if bits >= W::BITS {
+        return a;
+    }
     //处理溢出
-    a.value &= (1u64 << (size * 8)) - 1;
-    a.mask &= (1u64 << (size * 8)) - 1;
+    let keep = (W::ONE << bits) - W::ONE;
+    a.value = a.value & keep;
+    a.mask = a.mask & keep;
     a
 }
 
-pub fn tnum_is_aligned(a: Tnum, size: u64) -> bool {
-    if size == 0 {
-        return true;
+/// tnum 从低 from_bits 位符号扩展到整个字（MOVSX / LDSX），与 tnum_cast 的零扩展相对。
+/// 符号位已知时结果精确；符号位未知时高位全部未知，即两种扩展结果的 join
+pub fn tnum_sext<W: TnumWord>(a: Tnum<W>, from_bits: u8) -> Tnum<W> {
+    assert!(from_bits > 0, "cannot sign-extend from 0 bits");
+    if from_bits as u32 >= W::BITS {
+        return a;
+    }
+    let pad = W::BITS - from_bits as u32;
+    Tnum::new((a.value << pad).arshift(pad), (a.mask << pad).arshift(pad))
+}
+
+pub fn tnum_is_aligned<W: TnumWord>(a: Tnum<W>, size: W) -> bool {
+    if size == W::ZERO {
+        true
     } else {
-        return ((a.value | a.mask) & (size - 1)) == 0;
+        ((a.value | a.mask) & (size - W::ONE)) == W::ZERO
     }
 }
 
@@ -382,17 +961,28 @@ pub fn tnum_is_aligned(a: Tnum, size: u64) -> bool {
 /// check if [b] is a subset of [a], that is
 /// 1) for unknown bits: all bit-set in [b.mask] must exist in [a.mask]
 /// 2) for known bits: all bit-set in [b.value] must exist in [a.value] or [a.mask]
-pub fn tnum_in(a: Tnum, b: Tnum) -> bool {
-    if (b.mask & !a.mask) != 0 {
+pub fn tnum_in<W: TnumWord>(a: Tnum<W>, b: Tnum<W>) -> bool {
+    if (b.mask & !a.mask) != W::ZERO {
         // if we find one bit-set in [b.mask] but not in [a.mask], return false
-        return false;
+        false
     } else {
         // [(b.value & !a.mask)] removes all possible bit-set in [a.mask] from [b.value]
         // the rest part should be equal to [a.value]
-        return a.value == (b.value & !a.mask);
+        a.value == (b.value & !a.mask)
     }
 }
 
+/// 枚举 tnum 表示的所有具体值，共 2^unknown_count 个；BOTTOM 不表示任何值
+pub fn tnum_gamma<W: TnumWord>(a: Tnum<W>) -> impl Iterator<Item = W> + Clone {
+    // 从 mask 开始依次枚举 mask 的子集，到 0 为止
+    let mut sub = if a.is_bottom() { None } else { Some(a.mask) };
+    core::iter::from_fn(move || {
+        let s = sub?;
+        sub = if s == W::ZERO { None } else { Some((s - W::ONE) & a.mask) };
+        Some(a.value | s)
+    })
+}
+
 // pub fn xtnum_in(a: Tnum, b: Tnum) -> bool {
 //     if (b.mask & !a.mask) != 0 {
 //         return false;
@@ -402,38 +992,20 @@ pub fn tnum_in(a: Tnum, b: Tnum) -> bool {
 // }
 
 #[test]
-fn test_tnum_in () -> (){
-    let a = Tnum::new(1, 0);
-    let b = Tnum::new(0, 1);
+fn test_tnum_in () {
+    let a: Tnum = Tnum::new(1, 0);
+    let b: Tnum = Tnum::new(0, 1);
     println!("{:?}", tnum_in(b, a)); // true
     //println!("{:?}", xtnum_in(b, a)); // false
 }
 
-/// tnum转换为字符串
-pub fn tnum_sbin(size: usize, mut a: Tnum) -> String {
-    let mut result = vec![0u8; size];
-
-    // 从高位到低位处理每一位
-    for n in (1..=64).rev() {
-        if n < size {
-            result[n - 1] = match (a.mask & 1, a.value & 1) {
-                (1, _) => b'x', // 不确定位
-                (0, 1) => b'1', // 确定位 1
-                (0, 0) => b'0', // 确定位 0
-                _ => unreachable!(),
-            };
-        }
-        // 右移处理下一位
-        a.mask >>= 1;
-        a.value >>= 1;
-    }
-
-    // 设置字符串结束位置
-    let end = std::cmp::min(size - 1, 64);
-    result[end] = 0;
-
-    // 转换为字符串
-    String::from_utf8(result[..end].to_vec()).unwrap_or_else(|_| String::new())
+/// tnum转换为字符串（与内核 tnum_sbin 一致：从最高位起最多输出 size-1 位，
+/// 不带前缀）。完整的二进制/十六进制表示见 `Display`/`LowerHex`
+#[cfg(feature = "alloc")]
+pub fn tnum_sbin<W: TnumWord>(size: usize, a: Tnum<W>) -> String {
+    let n = core::cmp::min(size.saturating_sub(1), W::BITS as usize);
+    let full = format!("{:width$}", a, width = W::BITS as usize);
+    String::from(&full[2..2 + n])
 }
 
 pub fn tnum_subreg(a: Tnum) -> Tnum {
@@ -451,3 +1023,604 @@ pub fn tnum_with_subreg(reg: Tnum, subreg: Tnum) -> Tnum {
 pub fn tnum_const_subreg(a: Tnum, value: u32) -> Tnum {
     tnum_with_subreg(a, tnum_const(value as u64))
 }
+
+/// 32 位（ALU32）加法，结果零扩展到 64 位
+pub fn tnum_add_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_subreg(tnum_add_wrapping(tnum_subreg(a), tnum_subreg(b)))
+}
+
+/// 32 位（ALU32）减法，结果零扩展到 64 位
+pub fn tnum_sub_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_subreg(tnum_sub_wrapping(tnum_subreg(a), tnum_subreg(b)))
+}
+
+/// 32 位（ALU32）乘法，结果零扩展到 64 位
+pub fn tnum_mul_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_subreg(tnum_mul(tnum_subreg(a), tnum_subreg(b)))
+}
+
+/// 32 位（ALU32）无符号除法，结果零扩展到 64 位
+pub fn tnum_div_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_div(tnum_subreg(a), tnum_subreg(b))
+}
+
+/// 32 位（ALU32）无符号取模，结果零扩展到 64 位
+pub fn tnum_mod_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_mod(tnum_subreg(a), tnum_subreg(b))
+}
+
+/// 32 位（ALU32）按位与
+pub fn tnum_and_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_and(tnum_subreg(a), tnum_subreg(b))
+}
+
+/// 32 位（ALU32）按位或
+pub fn tnum_or_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_or(tnum_subreg(a), tnum_subreg(b))
+}
+
+/// 32 位（ALU32）按位异或
+pub fn tnum_xor_32(a: Tnum, b: Tnum) -> Tnum {
+    tnum_xor(tnum_subreg(a), tnum_subreg(b))
+}
+
+/// 32 位（ALU32）左移，shift 须小于 32
+pub fn tnum_lshift_32(a: Tnum, shift: u8) -> Tnum {
+    tnum_subreg(tnum_lshift(a, shift))
+}
+
+/// 32 位（ALU32）逻辑右移，先截断再移位，高 32 位不会移入结果
+pub fn tnum_rshift_32(a: Tnum, shift: u8) -> Tnum {
+    tnum_rshift(tnum_subreg(a), shift)
+}
+
+/// 32 位（ALU32）算术右移，符号位为第 31 位
+pub fn tnum_arshift_32(a: Tnum, shift: u8) -> Tnum {
+    tnum_arshift(a, shift, 32)
+}
+
+/// 32 位循环左移，结果零扩展
+pub fn tnum_rotl_32(a: Tnum, shift: u8) -> Tnum {
+    tnum_resize(tnum_rotl(tnum_resize::<u64, u32>(a), shift))
+}
+
+/// 32 位循环右移，结果零扩展
+pub fn tnum_rotr_32(a: Tnum, shift: u8) -> Tnum {
+    tnum_resize(tnum_rotr(tnum_resize::<u64, u32>(a), shift))
+}
+
+/// 移位量为 tnum 的 32 位循环左移，结果零扩展
+pub fn tnum_rotl_var_32(a: Tnum, shift: Tnum) -> Tnum {
+    tnum_resize(tnum_rotl_var(tnum_resize::<u64, u32>(a), tnum_resize(shift)))
+}
+
+/// 移位量为 tnum 的 32 位循环右移，结果零扩展
+pub fn tnum_rotr_var_32(a: Tnum, shift: Tnum) -> Tnum {
+    tnum_resize(tnum_rotr_var(tnum_resize::<u64, u32>(a), tnum_resize(shift)))
+}
+
+/// 低 16 位字节交换，结果零扩展（BPF_END / bswap16）
+pub fn tnum_bswap16(a: Tnum) -> Tnum {
+    Tnum::new((a.value as u16).swap_bytes() as u64, (a.mask as u16).swap_bytes() as u64)
+}
+
+/// 低 32 位字节交换，结果零扩展（BPF_END / bswap32）
+pub fn tnum_bswap32(a: Tnum) -> Tnum {
+    Tnum::new((a.value as u32).swap_bytes() as u64, (a.mask as u32).swap_bytes() as u64)
+}
+
+/// 64 位字节交换（BPF_END / bswap64）
+pub fn tnum_bswap64(a: Tnum) -> Tnum {
+    Tnum::new(a.value.swap_bytes(), a.mask.swap_bytes())
+}
+
+/// 字节序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    /// 小端
+    Little,
+    /// 大端
+    Big,
+}
+
+impl Endian {
+    /// 编译目标的字节序
+    pub const HOST: Endian = if cfg!(target_endian = "little") { Endian::Little } else { Endian::Big };
+}
+
+/// 以 target 为主机字节序转换为小端（BPF_TO_LE），width 为 16/32/64
+pub fn tnum_to_le(a: Tnum, width: u8, target: Endian) -> Tnum {
+    tnum_convert_endian(a, width, target, Endian::Little)
+}
+
+/// 以 target 为主机字节序转换为大端（BPF_TO_BE），width 为 16/32/64
+pub fn tnum_to_be(a: Tnum, width: u8, target: Endian) -> Tnum {
+    tnum_convert_endian(a, width, target, Endian::Big)
+}
+
+/// 字节序相同时只截断到 width 位，否则做字节交换
+fn tnum_convert_endian(a: Tnum, width: u8, from: Endian, to: Endian) -> Tnum {
+    if from == to {
+        tnum_cast(a, tnum_bswap_bytes(width))
+    } else {
+        tnum_bswap(a, width)
+    }
+}
+
+/// 检查 BPF_END 的宽度，返回字节数
+fn tnum_bswap_bytes(width: u8) -> u8 {
+    match width {
+        16 | 32 | 64 => width / 8,
+        _ => panic!("invalid BPF_END width {}", width),
+    }
+}
+
+/// 按宽度选择字节交换
+fn tnum_bswap(a: Tnum, width: u8) -> Tnum {
+    match tnum_bswap_bytes(width) {
+        2 => tnum_bswap16(a),
+        4 => tnum_bswap32(a),
+        _ => tnum_bswap64(a),
+    }
+}
+
+/// 在给定位上取 0/1/x 的全部 tnum（仅用于测试）
+#[cfg(test)]
+pub(crate) fn tnum_samples<W: TnumWord>(bits: &[u32]) -> Vec<Tnum<W>> {
+    let mut out = vec![Tnum::new(W::ZERO, W::ZERO)];
+    for &bit in bits {
+        out = out
+            .into_iter()
+            .flat_map(|t| {
+                [
+                    t,
+                    Tnum::new(t.value | (W::ONE << bit), t.mask),
+                    Tnum::new(t.value, t.mask | (W::ONE << bit)),
+                ]
+            })
+            .collect();
+    }
+    out
+}
+
+/// 二元转移函数（仅用于测试）
+#[cfg(test)]
+type BinOp<W> = fn(Tnum<W>, Tnum<W>) -> Tnum<W>;
+
+/// 穷举检查二元转移函数：断言结果包含所有具体结果（可靠），
+/// 并返回结果不等于这些具体结果的 join（不最优）的输入对（仅用于测试）
+#[cfg(test)]
+fn check_binary<W: TnumWord, V: TnumWord>(
+    xs: &[Tnum<W>],
+    ys: &[Tnum<W>],
+    op: impl Fn(Tnum<W>, Tnum<W>) -> Tnum<V>,
+    concrete: impl Fn(W, W) -> V,
+) -> Vec<(Tnum<W>, Tnum<W>)> {
+    let mut imprecise = Vec::new();
+    for &a in xs {
+        for &b in ys {
+            let r = op(a, b);
+            let mut best: Option<Tnum<V>> = None;
+            for x in tnum_gamma(a) {
+                for y in tnum_gamma(b) {
+                    let z = tnum_const(concrete(x, y));
+                    assert!(tnum_in(r, z), "{:?} {:?} -> {:?}", a, b, r);
+                    best = Some(best.map_or(z, |t| tnum_join(t, z)));
+                }
+            }
+            let best = best.unwrap();
+            if (best.value(), best.mask()) != (r.value(), r.mask()) {
+                imprecise.push((a, b));
+            }
+        }
+    }
+    imprecise
+}
+
+#[test]
+fn test_tnum_alu32() {
+    // 第 40 位在 32 位运算中必须被忽略
+    let samples = tnum_samples(&[0, 1, 31, 40]);
+    let check_alu32 = |op: BinOp<u64>, concrete: fn(u32, u32) -> u32| {
+        let op = |a, b| {
+            let r = op(a, b);
+            assert_eq!(r.value() >> 32 | r.mask() >> 32, 0);
+            r
+        };
+        check_binary(&samples, &samples, op, |x, y| concrete(x as u32, y as u32) as u64);
+    };
+    check_alu32(tnum_add_32, |x, y| x.wrapping_add(y));
+    check_alu32(tnum_sub_32, |x, y| x.wrapping_sub(y));
+    check_alu32(tnum_mul_32, |x, y| x.wrapping_mul(y));
+    check_alu32(tnum_div_32, |x, y| x.checked_div(y).unwrap_or(0));
+    check_alu32(tnum_mod_32, |x, y| x.checked_rem(y).unwrap_or(x));
+    check_alu32(tnum_and_32, |x, y| x & y);
+    check_alu32(tnum_or_32, |x, y| x | y);
+    check_alu32(tnum_xor_32, |x, y| x ^ y);
+    for &a in &samples {
+        for shift in 0..32u8 {
+            let l = tnum_lshift_32(a, shift);
+            let r = tnum_rshift_32(a, shift);
+            let ar = tnum_arshift_32(a, shift);
+            for x in tnum_gamma(a) {
+                let x = x as u32;
+                assert!(tnum_in(l, tnum_const((x << shift) as u64)));
+                assert!(tnum_in(r, tnum_const((x >> shift) as u64)));
+                assert!(tnum_in(ar, tnum_const(((x as i32) >> shift) as u32 as u64)));
+            }
+        }
+    }
+    // 先截断再右移：高位的未知位不会污染结果
+    let r = tnum_rshift_32(Tnum::new(0, 1 << 40), 4);
+    assert_eq!((r.value(), r.mask()), (0, 0));
+}
+
+#[test]
+fn test_tnum_exhaustive_u8() {
+    // 与 64 位相同的代码，在 8 位宽度上穷举
+    let all: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(all.len(), 6561);
+    for &a in &all {
+        for shift in 0..8u8 {
+            let l = tnum_lshift(a, shift);
+            let r = tnum_rshift(a, shift);
+            let ar = tnum_arshift(a, shift, 64);
+            for x in tnum_gamma(a) {
+                assert!(tnum_in(l, tnum_const(x << shift)));
+                assert!(tnum_in(r, tnum_const(x >> shift)));
+                assert!(tnum_in(ar, tnum_const(((x as i8) >> shift) as u8)));
+            }
+        }
+    }
+    // 二元运算：包含最高位以覆盖回绕；tnum_add 等非 wrapping 的算术只在不溢出的低 3 位上检查
+    let wide: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2, 6, 7]);
+    let low: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2]);
+    for &a in &wide {
+        for c in 0..=u8::MAX {
+            let r = tnum_mul_by_const(a, c);
+            for x in tnum_gamma(a) {
+                assert!(tnum_in(r, tnum_const(x.wrapping_mul(c))));
+            }
+        }
+    }
+    let check_u8 = |samples: &[Tnum<u8>], op: BinOp<u8>, concrete: fn(u8, u8) -> u8| {
+        check_binary(samples, samples, op, concrete);
+    };
+    check_u8(&wide, tnum_and, |x, y| x & y);
+    check_u8(&wide, tnum_or, |x, y| x | y);
+    check_u8(&wide, tnum_xor, |x, y| x ^ y);
+    check_u8(&wide, tnum_add_wrapping, |x, y| x.wrapping_add(y));
+    check_u8(&wide, tnum_sub_wrapping, |x, y| x.wrapping_sub(y));
+    check_u8(&wide, tnum_mul, |x, y| x.wrapping_mul(y));
+    check_u8(&wide, tnum_mul_opt, |x, y| x.wrapping_mul(y));
+    check_u8(&wide, xtnum_mul_top, |x, y| x.wrapping_mul(y));
+    check_u8(&wide, xtnum_mul_high_top, |x, y| x.wrapping_mul(y));
+    check_u8(&low, tnum_add, |x, y| x + y);
+    check_u8(&low, tnum_div, |x, y| x.checked_div(y).unwrap_or(0));
+    check_u8(&low, tnum_mod, |x, y| x.checked_rem(y).unwrap_or(x));
+}
+
+#[test]
+fn test_xtnum_mul_high_zero() {
+    // y 为常数 0 时 tnum_size(y) 为 0，不能再拆出最高位（b - 1 会下溢）
+    let zero = tnum_const(0u64);
+    for a in tnum_samples::<u64>(&[0, 1, 63]) {
+        assert_eq!(xtnum_mul_high_top(a, zero), zero, "{:?}", a);
+        assert_eq!(xtnum_mul_high_top(zero, a), zero, "{:?}", a);
+    }
+}
+
+#[test]
+fn test_tnum_mul_u128() {
+    // u128 上置位数之和可达 256，递归的燃料不能截断
+    let muls: [BinOp<u128>; 4] = [tnum_mul, tnum_mul_opt, xtnum_mul_top, xtnum_mul_high_top];
+    let top = Tnum::<u128>::TOP;
+    assert_eq!(tnum_size(top), 128);
+    let samples: Vec<Tnum<u128>> = tnum_samples(&[0, 1, 64, 126, 127]);
+    for mul in muls {
+        assert!(mul(top, top).is_top());
+        assert!(mul(Tnum::new(u128::MAX, 0), top).is_top());
+        check_binary(&samples, &samples, mul, u128::wrapping_mul);
+    }
+}
+
+#[test]
+fn test_tnum_mul_wide() {
+    let samples: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2, 6, 7]);
+    for &a in &samples {
+        for &b in &samples {
+            let (lo, hi) = tnum_mul_wide(a, b);
+            for x in tnum_gamma(a) {
+                for y in tnum_gamma(b) {
+                    let p = x as u16 * y as u16;
+                    assert!(tnum_in(lo, tnum_const(p as u8)));
+                    assert!(tnum_in(hi, tnum_const((p >> 8) as u8)));
+                }
+            }
+        }
+    }
+    let samples: Vec<Tnum> = tnum_samples(&[0, 1, 32, 62, 63]);
+    for &a in &samples {
+        for &b in &samples {
+            let (lo, hi) = tnum_mul_wide(a, b);
+            let narrow = tnum_mul(a, b);
+            assert_eq!((lo.value(), lo.mask()), (narrow.value(), narrow.mask()));
+            for x in tnum_gamma(a) {
+                for y in tnum_gamma(b) {
+                    let p = x as u128 * y as u128;
+                    assert!(tnum_in(lo, tnum_const(p as u64)));
+                    assert!(tnum_in(hi, tnum_const((p >> 64) as u64)));
+                }
+            }
+        }
+    }
+    let hi = tnum_mulhi(tnum_const(u64::MAX), tnum_const(u64::MAX));
+    assert_eq!((hi.value(), hi.mask()), (u64::MAX - 1, 0));
+}
+
+#[test]
+fn test_tnum_bswap() {
+    // 字节交换是位的置换：逐位取 0/1/x，并放在全 0、全 1、全 x 三种背景中，
+    // 对三种宽度、两种目标字节序检查结果恰为对 value 与 mask 的具体置换
+    for bit in 0..64 {
+        let one = 1u64 << bit;
+        for bg in [tnum_const(0u64), tnum_const(u64::MAX), Tnum::TOP] {
+            let base = Tnum::new(bg.value() & !one, bg.mask() & !one);
+            let trits = [base, Tnum::new(base.value() | one, base.mask()), Tnum::new(base.value(), base.mask() | one)];
+            for a in trits {
+                for width in [16u8, 32, 64] {
+                    let (swap, keep): (fn(u64) -> u64, u64) = match width {
+                        16 => (|x| (x as u16).swap_bytes() as u64, 0xffff),
+                        32 => (|x| (x as u32).swap_bytes() as u64, 0xffff_ffff),
+                        _ => (u64::swap_bytes, u64::MAX),
+                    };
+                    let swapped = Tnum::new(swap(a.value()), swap(a.mask()));
+                    let truncated = Tnum::new(a.value() & keep, a.mask() & keep);
+                    assert_eq!(tnum_bswap(a, width), swapped, "{:?} {}", a, width);
+                    assert_eq!(tnum_to_le(a, width, Endian::Little), truncated);
+                    assert_eq!(tnum_to_be(a, width, Endian::Big), truncated);
+                    assert_eq!(tnum_to_le(a, width, Endian::Big), swapped);
+                    assert_eq!(tnum_to_be(a, width, Endian::Little), swapped);
+                }
+            }
+        }
+    }
+    // Endian::HOST 与 core 的 to_le/to_be 一致
+    let x = 0x0102_0304_0506_0708u64;
+    assert_eq!(tnum_to_le(tnum_const(x), 64, Endian::HOST), tnum_const(x.to_le()));
+    assert_eq!(tnum_to_be(tnum_const(x), 32, Endian::HOST), tnum_const((x as u32).to_be() as u64));
+    let r = tnum_bswap16(Tnum::new(0xff_0012, 0x00_0f00));
+    assert_eq!((r.value(), r.mask()), (0x1200, 0x000f));
+}
+
+#[test]
+#[should_panic(expected = "invalid BPF_END width")]
+fn test_tnum_to_le_invalid_width() {
+    // 不需要交换时同样检查宽度
+    tnum_to_le(tnum_const(1), 8, Endian::Little);
+}
+
+#[test]
+fn test_tnum_sext() {
+    let all: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    for &a in &all {
+        for from in 1..=8u8 {
+            let r = tnum_sext(a, from);
+            let pad = 8 - from as u32;
+            // 结果应恰好是所有具体结果的 join
+            let best = tnum_gamma(a)
+                .map(|x| tnum_const((((x << pad) as i8) >> pad) as u8))
+                .reduce(tnum_join)
+                .unwrap();
+            assert_eq!((r.value(), r.mask()), (best.value(), best.mask()));
+        }
+    }
+    let r = tnum_sext(Tnum::new(0x80, 0), 8);
+    assert_eq!((r.value(), r.mask()), (0xffff_ffff_ffff_ff80u64, 0));
+    let r = tnum_sext(Tnum::new(0x1234, 0x8000), 16);
+    assert_eq!((r.value(), r.mask()), (0x1234u64, 0xffff_ffff_ffff_8000));
+}
+
+#[test]
+fn test_tnum_bit_counts() {
+    // 检查位计数的区间是紧的、tnum 结果是可靠的；一元运算的第二个操作数只取常数 0
+    let all: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    let check_count = |range: fn(Tnum<u8>) -> (u32, u32),
+                       tnum: fn(Tnum<u8>) -> Tnum<u8>,
+                       concrete: fn(u8) -> u32| {
+        for &a in &all {
+            let counts = tnum_gamma(a).map(concrete);
+            let expect = (counts.clone().min().unwrap(), counts.max().unwrap());
+            assert_eq!(range(a), expect, "{:?}", a);
+        }
+        check_binary(&all, &[tnum_const(0)], |a, _| tnum(a), |x, _| concrete(x) as u8);
+    };
+    check_count(tnum_popcount_range, tnum_popcount, u8::count_ones);
+    check_count(tnum_clz_range, tnum_clz, u8::leading_zeros);
+    check_count(tnum_ctz_range, tnum_ctz, u8::trailing_zeros);
+    assert_eq!(tnum_size(Tnum::new(0b100u64, 0b1000)), 4);
+}
+
+#[test]
+fn test_tnum_rotate() {
+    let all: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    let amounts: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 3]);
+    for &a in &all {
+        for k in 0..16u8 {
+            let l = tnum_rotl(a, k);
+            let r = tnum_rotr(a, k);
+            // 常数移位量时是精确的置换
+            assert_eq!(l.mask().count_ones(), a.mask().count_ones());
+            for x in tnum_gamma(a) {
+                assert!(tnum_in(l, tnum_const(x.rotate_left(k as u32))));
+                assert!(tnum_in(r, tnum_const(x.rotate_right(k as u32))));
+            }
+        }
+        for &s in &amounts {
+            let l = tnum_rotl_var(a, s);
+            let r = tnum_rotr_var(a, s);
+            for x in tnum_gamma(a) {
+                for k in tnum_gamma(s) {
+                    assert!(tnum_in(l, tnum_const(x.rotate_left(k as u32))));
+                    assert!(tnum_in(r, tnum_const(x.rotate_right(k as u32))));
+                }
+            }
+        }
+    }
+    let samples: Vec<Tnum> = tnum_samples(&[0, 5, 31, 40]);
+    let amounts: Vec<Tnum> = tnum_samples(&[0, 2, 4]);
+    for &a in &samples {
+        for &s in &amounts {
+            let l = tnum_rotl_var_32(a, s);
+            let r = tnum_rotr_var_32(a, s);
+            for x in tnum_gamma(a) {
+                for k in tnum_gamma(s) {
+                    let x = x as u32;
+                    let k = k as u8;
+                    assert!(tnum_in(l, tnum_const(x.rotate_left(k as u32) as u64)));
+                    assert!(tnum_in(r, tnum_const(x.rotate_right(k as u32) as u64)));
+                    assert!(tnum_in(tnum_rotl_32(a, k), tnum_const(x.rotate_left(k as u32) as u64)));
+                    assert!(tnum_in(tnum_rotr_32(a, k), tnum_const(x.rotate_right(k as u32) as u64)));
+                }
+            }
+        }
+    }
+    let r = tnum_rotl(Tnum::new(0x8000_0000_0000_0001u64, 0), 4);
+    assert_eq!((r.value(), r.mask()), (0x18, 0));
+    assert!(tnum_rotl_var(tnum_const(1u64), Tnum::BOTTOM).is_bottom());
+    assert!(tnum_rotr_var_32(tnum_const(1), Tnum::BOTTOM).is_bottom());
+}
+
+#[test]
+fn test_tnum_minmax_saturating() {
+    let samples: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2, 6, 7]);
+    // 精度：在位 0,1,2,6,7 上取样的 59049 个输入对中（不是全部 8 位 tnum），
+    // 结果恰为所有具体结果之 join 的对数。精度变化（变好或变差）都要更新这里
+    let report = |name: &str, op: BinOp<u8>, concrete: fn(u8, u8) -> u8, expected: usize| {
+        let total = samples.len() * samples.len();
+        let optimal = total - check_binary(&samples, &samples, op, concrete).len();
+        assert_eq!(optimal, expected, "{} optimal pairs out of {}", name, total);
+    };
+    report("umin", tnum_umin, core::cmp::min, 57145);
+    report("umax", tnum_umax, core::cmp::max, 57145);
+    report("smin", tnum_smin, |x, y| core::cmp::min(x as i8, y as i8) as u8, 57145);
+    report("smax", tnum_smax, |x, y| core::cmp::max(x as i8, y as i8) as u8, 57145);
+    report("saturating_add", tnum_saturating_add, u8::saturating_add, 59049);
+    report("saturating_sub", tnum_saturating_sub, u8::saturating_sub, 59049);
+}
+
+/// 8 位带溢出标志的二元转移函数（仅用于测试）
+#[cfg(test)]
+type OverflowingOp8 = fn(Tnum<u8>, Tnum<u8>) -> (Tnum<u8>, OverflowFlag);
+
+#[test]
+fn test_tnum_overflowing() {
+    let samples: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2, 6, 7]);
+    // 检查结果可靠、溢出标志精确：标志记作 1 位的 tnum，Never 为 0、Always 为 1、Maybe 为 x
+    let check_overflowing = |op: OverflowingOp8, concrete: fn(u8, u8) -> (u8, bool)| {
+        check_binary(&samples, &samples, |a, b| op(a, b).0, |x, y| concrete(x, y).0);
+        let flag = |a, b| match op(a, b).1 {
+            OverflowFlag::Never => tnum_const(0u8),
+            OverflowFlag::Always => tnum_const(1),
+            OverflowFlag::Maybe => Tnum::new(0, 1),
+        };
+        let imprecise = check_binary(&samples, &samples, flag, |x, y| concrete(x, y).1 as u8);
+        assert!(imprecise.is_empty(), "{:?}", imprecise);
+    };
+    check_overflowing(tnum_add_overflowing, u8::overflowing_add);
+    check_overflowing(tnum_sub_overflowing, u8::overflowing_sub);
+    check_overflowing(tnum_mul_overflowing, u8::overflowing_mul);
+    // 指针偏移：base + [0, 0xff] 不会回绕
+    let (_, flag) = tnum_add_overflowing(Tnum::new(0x1000u64, 0), Tnum::new(0, 0xff));
+    assert_eq!(flag, OverflowFlag::Never);
+}
+
+#[test]
+fn test_tnum_signed() {
+    use core::cmp::Ordering;
+    let all: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    for &a in &all {
+        let xs: Vec<i8> = tnum_gamma(a).map(|x| x as i8).collect();
+        assert_eq!(a.smin(), *xs.iter().min().unwrap());
+        assert_eq!(a.smax(), *xs.iter().max().unwrap());
+        assert_eq!(a.is_neg(), xs.iter().all(|&x| x < 0));
+        assert_eq!(a.is_nonneg(), xs.iter().all(|&x| x >= 0));
+        let abs = tnum_abs(a);
+        let neg = tnum_neg(a);
+        for &x in &xs {
+            assert!(tnum_in(abs, tnum_const(x.wrapping_abs() as u8)));
+            assert!(tnum_in(neg, tnum_const(x.wrapping_neg() as u8)));
+        }
+    }
+    let samples: Vec<Tnum<u8>> = tnum_samples(&[0, 3, 7]);
+    for &a in &samples {
+        for &b in &samples {
+            let ys: Vec<i8> = tnum_gamma(b).map(|y| y as i8).collect();
+            let all_cmp: Vec<Ordering> = tnum_gamma(a)
+                .flat_map(|x| ys.iter().map(move |&y| (x as i8).cmp(&y)))
+                .collect();
+            let expect = if all_cmp.iter().all(|&c| c == all_cmp[0]) {
+                Some(all_cmp[0])
+            } else {
+                None
+            };
+            assert_eq!(a.scmp(&b), expect, "{:?} {:?}", a, b);
+        }
+    }
+    assert_eq!(Tnum::new(0u64, 1 << 63).sign_bit(), None);
+    assert_eq!(Tnum::new(u64::MAX, 0).smin(), -1i64);
+}
+
+#[test]
+fn test_tnum_lattice() {
+    let top: Tnum<u8> = Tnum::unknown();
+    assert!(top.is_top() && top.is_unknown() && !top.is_const());
+    assert_eq!(top.unknown_count(), 8);
+    assert!(Tnum::<u8>::BOTTOM.is_bottom());
+    assert_eq!(tnum_gamma(Tnum::<u8>::BOTTOM).count(), 0);
+    assert_eq!(tnum_gamma(top).count(), 256);
+    assert_eq!(Tnum::new(5u8, 0).as_const(), Some(5));
+    assert_eq!(Tnum::new(4u8, 1).as_const(), None);
+    assert_eq!(Tnum::new(4u8, 1).known_bits(), 0xfe);
+    let samples: Vec<Tnum<u8>> = tnum_samples(&[0, 1, 7]);
+    for &a in &samples {
+        assert!(Tnum::BOTTOM < a && a <= top);
+        for &b in &samples {
+            let ga: Vec<u8> = tnum_gamma(a).collect();
+            let gb: Vec<u8> = tnum_gamma(b).collect();
+            let sub = ga.iter().all(|x| gb.contains(x));
+            let sup = gb.iter().all(|x| ga.contains(x));
+            assert_eq!(a <= b, sub, "{:?} {:?}", a, b);
+            assert_eq!(a >= b, sup, "{:?} {:?}", a, b);
+            assert_eq!(a == b, sub && sup);
+        }
+    }
+}
+
+#[test]
+fn test_tnum_range() {
+    // 与所有元素的 join 比较，确认是最优结果
+    for min in 0..=u8::MAX {
+        let mut best = tnum_const(min);
+        for max in min..=u8::MAX {
+            best = tnum_join(best, tnum_const(max));
+            assert_eq!(tnum_range(min, max), best, "[{}, {}]", min, max);
+        }
+    }
+    for min in 1..=u8::MAX {
+        let high = (min..=u8::MAX).map(tnum_const).reduce(tnum_join).unwrap();
+        let mut best = high;
+        for max in 0..min {
+            best = tnum_join(best, tnum_const(max));
+            assert_eq!(tnum_range_wrapped(min, max), best, "[{}, {}]", min, max);
+        }
+    }
+    let r = tnum_range(0x8000_0000_0000_0000u64, 0x8000_0000_0000_00ff);
+    assert_eq!((r.value(), r.mask()), (0x8000_0000_0000_0000, 0xff));
+    assert!(tnum_range(0u64, u64::MAX).is_top());
+    let r = tnum_srange(-4, -1);
+    assert_eq!((r.value(), r.mask()), (u64::MAX - 3, 3));
+    let r = tnum_srange(-1, 1);
+    assert!(r.is_top());
+    assert!(tnum_in(tnum_srange(-256, 255), tnum_range(0, 255)));
+}
@@ -0,0 +1,514 @@
+//! `tnum-bench`: benchmark and cross-check of the tnum multipliers
+//!
+//! ```text
+//! tnum-bench gen        --out PATH [--cases N] [--seed S]
+//! tnum-bench run        --in PATH --out PATH [--iterations I]
+//! tnum-bench compare    --in PATH [--report PATH] [--strict]
+//! tnum-bench exhaustive [--width W] [--strict]
+//! tnum-bench replay     --seed S --index I [--strict]
+//! ```
+//!
+//! `gen` draws the input cases, `run` times every multiplier of
+//! [MUL_METHODS] on them and `compare` checks the file the C harness
+//! (`src/tnum_mul.c`) has added `C_tnum_mul` results to. `exhaustive`
+//! checks all pairs of tnums up to 8 bits in process, `replay` one case.
+//!
+//! The exit status is 0 when nothing was found, 1 on a mismatch and 2 on
+//! usage or I/O errors. A mismatch is a result that shares no value with
+//! the C reference (so one of the two is unsound), a broken soundness
+//! claim or a kernel C differential mismatch. With `--strict` every
+//! difference from the reference counts.
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::Instant;
+use tnum::tnum::{tnum_const, tnum_gamma, tnum_in, tnum_join, tnum_mul, tnum_mul_by_const, Tnum};
+use tnum::tnum_c::{self, diff_all, DiffMismatch, DIFF_OPS};
+use tnum::tnum_fmt::tnum_strn;
+use tnum::tnum_methods::{MUL_METHODS, REFERENCE_METHOD};
+use tnum::tnum_rand::{TnumGen, TnumGenConfig};
+
+const USAGE: &str = "usage:
+  tnum-bench gen        --out PATH [--cases N] [--seed S]
+  tnum-bench run        --in PATH --out PATH [--iterations I]
+  tnum-bench compare    --in PATH [--report PATH] [--strict]
+  tnum-bench exhaustive [--width W] [--strict]
+  tnum-bench replay     --seed S --index I [--strict]";
+
+/// Ok(true) 表示没有发现不一致
+type Outcome = Result<bool, Box<dyn Error>>;
+
+/// 一个测试用例：输入与各实现的结果
+#[derive(Debug, Serialize, Deserialize)]
+struct TestCase {
+    /// 生成器种子与用例序号，`replay --seed <seed> --index <index>` 可重现
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    input_a: Tnum,
+    input_b: Tnum,
+    #[serde(default)]
+    results: Vec<MethodResult>,
+}
+
+/// 测试方法结果结构
+#[derive(Debug, Serialize, Deserialize)]
+struct MethodResult {
+    method: String,
+    output: Tnum,
+    avg_time_ns: f64,
+}
+
+/// 不一致结果
+#[derive(Serialize)]
+struct Inconsistency {
+    case_number: u32,
+    seed: Option<u64>,
+    index: Option<usize>,
+    input_a: Tnum,
+    input_b: Tnum,
+    c_output: Tnum,
+    rust_output: Tnum,
+    method: String,
+}
+
+/// compare 的统计信息
+struct MethodStats {
+    method: &'static str,
+    equal: u32,
+    less_than: u32,
+    more_than: u32,
+    not_equal: u32,
+    /// not_equal 中与 C 结果没有公共值的
+    disjoint: u32,
+    total_count: u32,
+    total_time: f64,
+}
+
+impl MethodStats {
+    fn new(method: &'static str) -> Self {
+        MethodStats {
+            method,
+            equal: 0,
+            less_than: 0,
+            more_than: 0,
+            not_equal: 0,
+            disjoint: 0,
+            total_count: 0,
+            total_time: 0.0,
+        }
+    }
+}
+
+/// 子命令之后的参数，只有 `--name value` 和开关
+struct Args(Vec<String>);
+
+impl Args {
+    fn value(&mut self, name: &str) -> Result<Option<String>, String> {
+        let Some(i) = self.0.iter().position(|a| a == name) else {
+            return Ok(None);
+        };
+        self.0.remove(i);
+        match self.0.get(i) {
+            Some(v) if !v.starts_with("--") => Ok(Some(self.0.remove(i))),
+            _ => Err(format!("{} expects a value", name)),
+        }
+    }
+
+    fn parse<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, String> {
+        self.value(name)?
+            .map(|v| v.parse().map_err(|_| format!("invalid value for {}: {}", name, v)))
+            .transpose()
+    }
+
+    fn required<T: FromStr>(&mut self, name: &str) -> Result<T, String> {
+        self.parse(name)?.ok_or_else(|| format!("missing {}", name))
+    }
+
+    fn switch(&mut self, name: &str) -> bool {
+        let found = self.0.iter().any(|a| a == name);
+        self.0.retain(|a| a != name);
+        found
+    }
+
+    /// 所有参数都应已被取走
+    fn finish(self) -> Result<(), String> {
+        match self.0.first() {
+            Some(a) => Err(format!("unexpected argument: {}", a)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &str) -> Result<T, Box<dyn Error>> {
+    let json = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(serde_json::from_str(&json).map_err(|e| format!("{}: {}", path, e))?)
+}
+
+fn write_json<T: Serialize>(path: &str, data: &T) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(data)?).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(())
+}
+
+/// 两个 tnum 没有公共值：其中至少一个不可靠
+fn disjoint(a: Tnum, b: Tnum) -> bool {
+    (a.value() ^ b.value()) & !(a.mask() | b.mask()) != 0
+}
+
+/// 与基准结果比较时是否算作不一致
+fn mismatch(r: Tnum, reference: Tnum, strict: bool) -> bool {
+    if strict {
+        r != reference
+    } else {
+        disjoint(r, reference)
+    }
+}
+
+fn gen_config(seed: u64) -> TnumGenConfig {
+    TnumGenConfig { seed, ..Default::default() }
+}
+
+fn run_method_test(
+    method_name: &str,
+    mul_fn: impl Fn(Tnum, Tnum) -> Tnum,
+    a: Tnum,
+    b: Tnum,
+    iterations: usize,
+) -> MethodResult {
+    let mut times = Vec::with_capacity(iterations);
+    let mut result = None;
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        result = Some(mul_fn(a, b));
+        times.push(start.elapsed().as_nanos());
+    }
+
+    MethodResult {
+        method: method_name.to_string(),
+        output: result.unwrap(),
+        avg_time_ns: times.iter().sum::<u128>() as f64 / iterations as f64,
+    }
+}
+
+fn print_diff(mismatches: &[DiffMismatch]) {
+    println!("\nKernel C differential:");
+    for (name, _) in DIFF_OPS {
+        let count = mismatches.iter().filter(|m| m.op == *name).count();
+        println!("{:<30} {} mismatches", name, count);
+    }
+    for m in mismatches.iter().take(10) {
+        println!("{:?}", m);
+    }
+}
+
+/// 生成用例。第 i 个用例固定是生成器的第 2i、2i+1 次输出
+fn gen(mut args: Args) -> Outcome {
+    let out: String = args.required("--out")?;
+    let n: usize = args.parse("--cases")?.unwrap_or(1000);
+    let seed = args.parse("--seed")?.unwrap_or_else(rand::random);
+    args.finish()?;
+
+    let mut gen = TnumGen::new(gen_config(seed));
+    let cases: Vec<TestCase> = (0..n)
+        .map(|index| TestCase {
+            seed: Some(seed),
+            index: Some(index),
+            input_a: gen.next_tnum(),
+            input_b: gen.next_tnum(),
+            results: Vec::new(),
+        })
+        .collect();
+    write_json(&out, &cases)?;
+    println!("{} cases with seed {} written to {}", n, seed, out);
+    Ok(true)
+}
+
+/// 为每个用例计时所有 Rust 实现，并在这些输入上做内核 C 差分
+fn run(mut args: Args) -> Outcome {
+    let input: String = args.required("--in")?;
+    let out: String = args.required("--out")?;
+    let iterations: usize = args.parse("--iterations")?.unwrap_or(1000);
+    args.finish()?;
+    if iterations == 0 {
+        return Err("--iterations must be positive".into());
+    }
+
+    let mut cases: Vec<TestCase> = read_json(&input)?;
+    let n = cases.len().max(1) as f64;
+    println!("Start {} Test Cases，each one repeats {} times...", cases.len(), iterations);
+
+    let mut total_times = vec![0.0; MUL_METHODS.len()];
+    for case in &mut cases {
+        let (a, b) = (case.input_a, case.input_b);
+        case.results = MUL_METHODS
+            .iter()
+            .map(|m| run_method_test(m.name, m.func, a, b, iterations))
+            .collect();
+        for (total, result) in total_times.iter_mut().zip(&case.results) {
+            *total += result.avg_time_ns;
+        }
+    }
+
+    println!("\nTotal:");
+    println!("function\t\t\t\t\taverage time(ns)");
+    println!("----------------------------------------");
+    for (m, total) in MUL_METHODS.iter().zip(&total_times) {
+        println!("{:<30} {:<20.2}", m.name, total / n);
+    }
+
+    // 常数乘数：以 input_b 的 value 作常数，tnum_mul 与 tnum_mul_by_const 对比
+    let mut const_times = [0.0; 2];
+    let mut more_precise = 0;
+    for case in &cases {
+        let (a, c) = (case.input_a, case.input_b.value());
+        let base = run_method_test("tnum_mul", tnum_mul, a, tnum_const(c), iterations);
+        let fast = run_method_test("tnum_mul_by_const", |x, _| tnum_mul_by_const(x, c), a, a, iterations);
+        const_times[0] += base.avg_time_ns;
+        const_times[1] += fast.avg_time_ns;
+        if fast.output.mask() != base.output.mask() && tnum_in(base.output, fast.output) {
+            more_precise += 1;
+        }
+    }
+    println!("\nConstant multiplier:");
+    println!("{:<30} {:<20.2}", "tnum_mul", const_times[0] / n);
+    println!("{:<30} {:<20.2}", "tnum_mul_by_const", const_times[1] / n);
+    println!("tnum_mul_by_const more precise in {}/{} cases", more_precise, cases.len());
+
+    let inputs: Vec<Tnum> = cases.iter().flat_map(|c| [c.input_a, c.input_b]).collect();
+    let mismatches = diff_all(&inputs);
+    print_diff(&mismatches);
+
+    write_json(&out, &cases)?;
+    println!("\nAll info are stored in：{}", out);
+    Ok(mismatches.is_empty())
+}
+
+/// 以 C_tnum_mul 为基准比较结果文件中的所有实现
+fn compare(mut args: Args) -> Outcome {
+    let input: String = args.required("--in")?;
+    let report = args.value("--report")?.unwrap_or_else(|| "inconsistencies.json".to_string());
+    let strict = args.switch("--strict");
+    args.finish()?;
+
+    let test_cases: Vec<TestCase> = read_json(&input)?;
+    println!("分析 {} 个测试用例...", test_cases.len());
+
+    let mut stats: Vec<MethodStats> = std::iter::once(REFERENCE_METHOD)
+        .chain(MUL_METHODS.iter().map(|m| m.name))
+        .map(MethodStats::new)
+        .collect();
+    let mut inconsistencies: Vec<Inconsistency> = Vec::new();
+    let mut failures = 0;
+
+    for (i, test_case) in test_cases.iter().enumerate() {
+        // 没有 C_tnum_mul 的结果则跳过
+        let Some(c_result) = test_case.results.iter().find(|r| r.method == REFERENCE_METHOD) else {
+            continue;
+        };
+        let c_output = c_result.output;
+        // C 和自己比总是正确的
+        stats[0].total_count += 1;
+        stats[0].equal += 1;
+        stats[0].total_time += c_result.avg_time_ns;
+
+        for result in test_case.results.iter().filter(|r| r.method != REFERENCE_METHOD) {
+            // 不在注册表中的实现不统计
+            let Some(stat) = stats.iter_mut().find(|s| s.method == result.method) else {
+                continue;
+            };
+            stat.total_count += 1;
+            stat.total_time += result.avg_time_ns;
+            if result.output == c_output {
+                stat.equal += 1;
+                continue;
+            }
+            if tnum_in(result.output, c_output) {
+                stat.less_than += 1;
+            } else if tnum_in(c_output, result.output) {
+                stat.more_than += 1;
+            } else {
+                stat.not_equal += 1;
+                if disjoint(result.output, c_output) {
+                    stat.disjoint += 1;
+                }
+            }
+            if mismatch(result.output, c_output, strict) {
+                failures += 1;
+            }
+            inconsistencies.push(Inconsistency {
+                case_number: (i + 1) as u32,
+                seed: test_case.seed,
+                index: test_case.index,
+                input_a: test_case.input_a,
+                input_b: test_case.input_b,
+                c_output,
+                rust_output: result.output,
+                method: result.method.clone(),
+            });
+        }
+    }
+
+    if stats[0].total_count == 0 {
+        return Err(format!("{}: no {} results", input, REFERENCE_METHOD).into());
+    }
+
+    println!(
+        "\n{:<24} {:<18} {:<18} {:<18} {:<18} {:<18} {:<18}",
+        "method", "average time(ns)", "equal", "less than", "more than", "not_equal", "disjoint"
+    );
+    println!("------------------------------------------------------------------------");
+    for stat in stats.iter().filter(|s| s.total_count > 0) {
+        let pct = |x: u32| x as f64 / stat.total_count as f64 * 100.0;
+        println!(
+            "{:<24} {:<18.1} {:<18.1} {:<18.1} {:<18.1} {:<18.1} {:<18.1}",
+            stat.method,
+            stat.total_time / stat.total_count as f64,
+            pct(stat.equal),
+            pct(stat.less_than),
+            pct(stat.more_than),
+            pct(stat.not_equal),
+            pct(stat.disjoint)
+        );
+    }
+
+    if inconsistencies.is_empty() {
+        println!("\n所有实现的结果与C_tnum_mul完全一致！");
+    } else {
+        write_json(&report, &inconsistencies)?;
+        println!("\n不一致结果已保存到: {}", report);
+        if let (Some(seed), Some(index)) = (inconsistencies[0].seed, inconsistencies[0].index) {
+            println!("重现第一个: tnum-bench replay --seed {} --index {}", seed, index);
+        }
+    }
+    println!("{} mismatches", failures);
+    Ok(failures == 0)
+}
+
+/// 低 width 位上的所有 tnum
+fn all_tnums(width: u32) -> Vec<Tnum> {
+    let top = 1u64 << width;
+    (0..top)
+        .flat_map(|mask| (0..top).filter(move |v| v & mask == 0).map(move |v| Tnum::new(v, mask)))
+        .collect()
+}
+
+/// 所有 width 位 tnum 对上，与最精确结果及 C 实现比较每个乘法
+fn exhaustive(mut args: Args) -> Outcome {
+    let width: u32 = args.parse("--width")?.unwrap_or(4);
+    let strict = args.switch("--strict");
+    args.finish()?;
+    if !(1..=8).contains(&width) {
+        return Err("--width must be in 1..=8".into());
+    }
+
+    let inputs = all_tnums(width);
+    // (不可靠, 最精确, 与 C 相同, 第一个不一致的用例)
+    let mut stats = vec![(0u64, 0u64, 0u64, None); MUL_METHODS.len()];
+    let mut c_unsound = 0u64;
+    for &a in &inputs {
+        for &b in &inputs {
+            // 乘积不超过 16 位，不会溢出
+            let best = tnum_gamma(a)
+                .flat_map(|x| tnum_gamma(b).map(move |y| tnum_const(x * y)))
+                .reduce(tnum_join)
+                .unwrap();
+            let c = tnum_c::tnum_mul(a, b);
+            if !tnum_in(c, best) {
+                c_unsound += 1;
+            }
+            for (m, s) in MUL_METHODS.iter().zip(&mut stats) {
+                let r = (m.func)(a, b);
+                let unsound = !tnum_in(r, best);
+                s.0 += unsound as u64;
+                s.1 += (r == best) as u64;
+                s.2 += (r == c) as u64;
+                if s.3.is_none() && ((unsound && m.sound) || mismatch(r, c, strict)) {
+                    s.3 = Some((a, b, r, c));
+                }
+            }
+        }
+    }
+
+    let pairs = (inputs.len() * inputs.len()) as f64;
+    println!("{} tnums of width {}, {} pairs", inputs.len(), width, pairs);
+    println!("{:<24} {:<12} {:<12} {:<12}", "method", "unsound", "optimal %", "equal C %");
+    println!("{:<24} {:<12}", REFERENCE_METHOD, c_unsound);
+    let mut clean = c_unsound == 0;
+    for (m, (unsound, optimal, equal, first)) in MUL_METHODS.iter().zip(&stats) {
+        println!(
+            "{:<24} {:<12} {:<12.1} {:<12.1}",
+            m.name,
+            unsound,
+            *optimal as f64 / pairs * 100.0,
+            *equal as f64 / pairs * 100.0
+        );
+        if let Some((a, b, r, c)) = first {
+            clean = false;
+            println!("  {} * {} = {}, C: {}", tnum_strn(*a), tnum_strn(*b), tnum_strn(*r), tnum_strn(*c));
+        }
+    }
+
+    let mismatches = diff_all(&inputs);
+    print_diff(&mismatches);
+    Ok(clean && mismatches.is_empty())
+}
+
+/// 重新生成 gen 的第 index 个用例，打印每个实现的结果
+fn replay(mut args: Args) -> Outcome {
+    let seed: u64 = args.required("--seed")?;
+    let index: usize = args.required("--index")?;
+    let strict = args.switch("--strict");
+    args.finish()?;
+
+    let mut gen = TnumGen::new(gen_config(seed));
+    let a = gen.nth(2 * index).unwrap();
+    let b = gen.next_tnum();
+    println!("case {} of seed {}", index, seed);
+    println!("input_a {:<44} {}", tnum_strn(a), a);
+    println!("input_b {:<44} {}", tnum_strn(b), b);
+    let reference = tnum_c::tnum_mul(a, b);
+    println!("{:<20} {}", REFERENCE_METHOD, tnum_strn(reference));
+    let mut clean = true;
+    for m in MUL_METHODS {
+        let r = (m.func)(a, b);
+        let verdict = if r == reference { "" } else if disjoint(r, reference) { "disjoint" } else { "differs" };
+        clean &= !mismatch(r, reference, strict);
+        println!("{:<20} {:<44} {}", m.name, tnum_strn(r), verdict);
+    }
+    Ok(clean)
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let command = args.next();
+    let args = Args(args.collect());
+    let outcome = match command.as_deref() {
+        Some("gen") => gen(args),
+        Some("run") => run(args),
+        Some("compare") => compare(args),
+        Some("exhaustive") => exhaustive(args),
+        Some("replay") => replay(args),
+        Some("help" | "--help" | "-h") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        _ => Err(USAGE.into()),
+    };
+    match outcome {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("tnum-bench: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
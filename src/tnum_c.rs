@@ -0,0 +1,180 @@
+//! The kernel C implementation (`src/tnum.c`), linked in by `build.rs`
+//!
+//! Only the functions that `tnum.c` actually defines are bound. They take
+//! and return `Tnum<u64>`, which is `#[repr(C)]` and matches `struct tnum`.
+//! [DIFF_OPS] pairs each of them with its Rust twin, and [diff_all] checks
+//! the pairs bit for bit on a set of inputs, in process, without the JSON
+//! round-trip through `tnum_mul.c`.
+
+use crate::tnum::{self, Tnum};
+use alloc::vec::Vec;
+
+mod sys {
+    use crate::tnum::Tnum;
+
+    extern "C" {
+        pub fn tnum_lshift(a: Tnum, shift: u8) -> Tnum;
+        pub fn tnum_rshift(a: Tnum, shift: u8) -> Tnum;
+        pub fn tnum_add(a: Tnum, b: Tnum) -> Tnum;
+        pub fn tnum_sub(a: Tnum, b: Tnum) -> Tnum;
+        pub fn tnum_and(a: Tnum, b: Tnum) -> Tnum;
+        pub fn tnum_mul(a: Tnum, b: Tnum) -> Tnum;
+        pub fn tnum_in(a: Tnum, b: Tnum) -> bool;
+    }
+}
+
+/// C 版本的 tnum_lshift，shift 必须小于 64
+pub fn tnum_lshift(a: Tnum, shift: u8) -> Tnum {
+    assert!(shift < 64);
+    unsafe { sys::tnum_lshift(a, shift) }
+}
+
+/// C 版本的 tnum_rshift，shift 必须小于 64
+pub fn tnum_rshift(a: Tnum, shift: u8) -> Tnum {
+    assert!(shift < 64);
+    unsafe { sys::tnum_rshift(a, shift) }
+}
+
+/// C 版本的 tnum_add
+pub fn tnum_add(a: Tnum, b: Tnum) -> Tnum {
+    unsafe { sys::tnum_add(a, b) }
+}
+
+/// C 版本的 tnum_sub
+pub fn tnum_sub(a: Tnum, b: Tnum) -> Tnum {
+    unsafe { sys::tnum_sub(a, b) }
+}
+
+/// C 版本的 tnum_and
+pub fn tnum_and(a: Tnum, b: Tnum) -> Tnum {
+    unsafe { sys::tnum_and(a, b) }
+}
+
+/// C 版本的 tnum_mul
+pub fn tnum_mul(a: Tnum, b: Tnum) -> Tnum {
+    unsafe { sys::tnum_mul(a, b) }
+}
+
+/// C 版本的 tnum_in
+pub fn tnum_in(a: Tnum, b: Tnum) -> bool {
+    unsafe { sys::tnum_in(a, b) }
+}
+
+/// 差分测试的一个操作：Rust 实现与它的 C 版本
+#[derive(Clone, Copy)]
+pub enum DiffOp {
+    Binary(fn(Tnum, Tnum) -> Tnum, fn(Tnum, Tnum) -> Tnum),
+    /// 第二个操作数是立即数（移位量）
+    BinaryImm(fn(Tnum, u8) -> Tnum, fn(Tnum, u8) -> Tnum),
+    Predicate(fn(Tnum, Tnum) -> bool, fn(Tnum, Tnum) -> bool),
+}
+
+/// 所有有 C 版本的操作。add/sub 用回绕版本，与内核的 u64 运算一致（tnum_mul 本身回绕）
+pub const DIFF_OPS: &[(&str, DiffOp)] = &[
+    ("add", DiffOp::Binary(tnum::tnum_add_wrapping, tnum_add)),
+    ("sub", DiffOp::Binary(tnum::tnum_sub_wrapping, tnum_sub)),
+    ("and", DiffOp::Binary(tnum::tnum_and, tnum_and)),
+    ("mul", DiffOp::Binary(tnum::tnum_mul, tnum_mul)),
+    ("lshift", DiffOp::BinaryImm(tnum::tnum_lshift, tnum_lshift)),
+    ("rshift", DiffOp::BinaryImm(tnum::tnum_rshift, tnum_rshift)),
+    ("in", DiffOp::Predicate(tnum::tnum_in, tnum_in)),
+];
+
+/// 立即数操作尝试的取值
+pub const DIFF_IMMS: [u8; 7] = [0, 1, 7, 31, 32, 33, 63];
+
+/// 一个操作的输出
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOutput {
+    /// (value, mask)，逐位比较
+    Tnum(u64, u64),
+    Bool(bool),
+}
+
+impl From<Tnum> for DiffOutput {
+    fn from(a: Tnum) -> Self {
+        DiffOutput::Tnum(a.value(), a.mask())
+    }
+}
+
+/// Rust 与 C 结果不一致的一个用例
+#[derive(Debug, Clone)]
+pub struct DiffMismatch {
+    pub op: &'static str,
+    pub a: Tnum,
+    /// 立即数操作没有第二个 tnum 操作数
+    pub b: Option<Tnum>,
+    pub imm: Option<u8>,
+    pub rust: DiffOutput,
+    pub c: DiffOutput,
+}
+
+impl DiffOp {
+    /// 在所有输入（二元操作为所有输入对）上比较两个实现
+    pub fn check(&self, name: &'static str, inputs: &[Tnum], out: &mut Vec<DiffMismatch>) {
+        let mut push = |a: Tnum, b: Option<Tnum>, imm: Option<u8>, rust: DiffOutput, c: DiffOutput| {
+            if rust != c {
+                out.push(DiffMismatch { op: name, a, b, imm, rust, c });
+            }
+        };
+        for &a in inputs {
+            match *self {
+                DiffOp::BinaryImm(r, c) => {
+                    for imm in DIFF_IMMS {
+                        push(a, None, Some(imm), r(a, imm).into(), c(a, imm).into());
+                    }
+                }
+                DiffOp::Binary(r, c) => {
+                    for &b in inputs {
+                        push(a, Some(b), None, r(a, b).into(), c(a, b).into());
+                    }
+                }
+                DiffOp::Predicate(r, c) => {
+                    for &b in inputs {
+                        push(a, Some(b), None, DiffOutput::Bool(r(a, b)), DiffOutput::Bool(c(a, b)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 在同一组输入上比较 [DIFF_OPS] 中的全部操作
+pub fn diff_all(inputs: &[Tnum]) -> Vec<DiffMismatch> {
+    let mut out = Vec::new();
+    for (name, op) in DIFF_OPS {
+        op.check(name, inputs, &mut out);
+    }
+    out
+}
+
+/// 差分测试的输入：边界位上的全部组合加上固定种子的随机 tnum
+#[cfg(test)]
+fn diff_inputs() -> Vec<Tnum> {
+    use crate::tnum_rand::{TnumGen, TnumGenConfig};
+
+    let mut inputs = crate::tnum::tnum_samples(&[0, 1, 31, 32, 63]);
+    let config = TnumGenConfig { seed: 0x7e57, edge_rate: 0.2, ..Default::default() };
+    inputs.extend(TnumGen::new(config).take(200));
+    inputs
+}
+
+#[test]
+fn test_tnum_c_diff() {
+    let inputs = diff_inputs();
+    let mismatches = diff_all(&inputs);
+    assert!(mismatches.is_empty(), "{:#?}", &mismatches[..mismatches.len().min(5)]);
+}
+
+#[test]
+fn test_tnum_c_diff_detects_mismatch() {
+    // 故意配错的一对：Rust 加法对 C 减法
+    let op = DiffOp::Binary(tnum::tnum_add_wrapping, tnum_sub);
+    let mut out = Vec::new();
+    op.check("add_vs_sub", &diff_inputs(), &mut out);
+    assert!(!out.is_empty());
+    let m = &out[0];
+    assert_eq!((m.op, m.imm), ("add_vs_sub", None));
+    assert_eq!(m.rust, tnum::tnum_add_wrapping(m.a, m.b.unwrap()).into());
+    assert_eq!(m.c, tnum_sub(m.a, m.b.unwrap()).into());
+}
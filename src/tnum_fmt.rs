@@ -0,0 +1,311 @@
+//! Text notations of a tnum
+//!
+//! - binary: `0b10xx01`, one trit per bit
+//! - nibble hex: `0x1x[10x1]f`, a nibble is a hex digit when known,
+//!   `x` when fully unknown and a bracketed group of four trits otherwise
+//! - verifier log: `(0x0; 0xff)`, value and mask as printed by the
+//!   kernel's `tnum_strn` (e.g. `var_off=(0x0; 0xff)`), shown through the
+//!   [TnumStrn] adapter
+//!
+//! The shortest form is printed by default; the formatter width pads with
+//! known zeros up to that many digits (bits for `{}`, nibbles for `{:x}`),
+//! capped at the word size.
+
+use crate::tnum::{tnum_size, Tnum, TnumWord};
+#[cfg(test)]
+use crate::tnum::tnum_range;
+#[cfg(all(test, feature = "alloc"))]
+use crate::tnum::tnum_sbin;
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+use core::fmt;
+use core::str::FromStr;
+
+/// 解析 tnum 字符串时的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTnumError {
+    /// 缺少 `0b` / `0x` 前缀、`(value; mask)` 格式不对或没有数字
+    Empty,
+    /// 非法字符
+    InvalidDigit(char),
+    /// `[...]` 组不是恰好 4 位，或括号不匹配
+    InvalidGroup,
+    /// 超出字宽
+    TooLong,
+    /// value 与 mask 有交集
+    NotCanonical,
+}
+
+impl fmt::Display for ParseTnumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseTnumError::Empty => write!(f, "expected 0b/0x digits or (0x..; 0x..)"),
+            ParseTnumError::InvalidDigit(c) => write!(f, "invalid tnum digit {:?}", c),
+            ParseTnumError::InvalidGroup => write!(f, "a [...] group must hold exactly 4 trits"),
+            ParseTnumError::TooLong => write!(f, "tnum literal does not fit in the word"),
+            ParseTnumError::NotCanonical => write!(f, "value and mask overlap"),
+        }
+    }
+}
+
+impl core::error::Error for ParseTnumError {}
+
+/// 第 i 位的字符
+fn trit<W: TnumWord>(a: &Tnum<W>, i: u32) -> char {
+    if (a.mask() >> i) & W::ONE != W::ZERO {
+        'x'
+    } else if (a.value() >> i) & W::ONE != W::ZERO {
+        '1'
+    } else {
+        '0'
+    }
+}
+
+/// 第 i 个半字节（4 位）的 value 与 mask
+fn nibble<W: TnumWord>(a: &Tnum<W>, i: u32) -> (u8, u8) {
+    let low = W::from_u128(0xf);
+    let v = ((a.value() >> (4 * i)) & low).to_u128() as u8;
+    let m = ((a.mask() >> (4 * i)) & low).to_u128() as u8;
+    (v, m)
+}
+
+impl<W: TnumWord> fmt::Display for Tnum<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shortest = core::cmp::max(tnum_size(*self), 1);
+        let digits = core::cmp::min(core::cmp::max(f.width().unwrap_or(0) as u32, shortest), W::BITS);
+        f.write_str("0b")?;
+        for i in (0..digits).rev() {
+            write!(f, "{}", trit(self, i))?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: TnumWord> fmt::LowerHex for Tnum<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shortest = core::cmp::max(tnum_size(*self).div_ceil(4), 1);
+        let digits = core::cmp::min(core::cmp::max(f.width().unwrap_or(0) as u32, shortest), W::BITS.div_ceil(4));
+        f.write_str("0x")?;
+        for i in (0..digits).rev() {
+            match nibble(self, i) {
+                (v, 0) => write!(f, "{:x}", v)?,
+                (_, 0xf) => f.write_str("x")?,
+                _ => {
+                    f.write_str("[")?;
+                    for j in (0..4).rev() {
+                        write!(f, "{}", trit(self, 4 * i + j))?;
+                    }
+                    f.write_str("]")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 以内核 tnum_strn 的 `(value; mask)` 表示显示 tnum，不需要 alloc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TnumStrn<W: TnumWord = u64>(pub Tnum<W>);
+
+impl<W: TnumWord> fmt::Display for TnumStrn<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(0x{:x}; 0x{:x})", self.0.value().to_u128(), self.0.mask().to_u128())
+    }
+}
+
+/// 与内核 tnum_strn 相同的 `(value; mask)` 表示
+#[cfg(feature = "alloc")]
+pub fn tnum_strn<W: TnumWord>(a: Tnum<W>) -> String {
+    format!("{}", TnumStrn(a))
+}
+
+/// 解析 `0x` 开头的十六进制整数，不能超出字宽
+fn parse_hex_word<W: TnumWord>(s: &str) -> Result<W, ParseTnumError> {
+    let digits = s.strip_prefix("0x").ok_or(ParseTnumError::Empty)?;
+    if digits.is_empty() {
+        return Err(ParseTnumError::Empty);
+    }
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(ParseTnumError::InvalidDigit(c));
+    }
+    match u128::from_str_radix(digits, 16) {
+        Ok(v) if v <= W::MAX.to_u128() => Ok(W::from_u128(v)),
+        _ => Err(ParseTnumError::TooLong),
+    }
+}
+
+/// 解析 `(value; mask)`
+fn parse_strn<W: TnumWord>(s: &str) -> Result<Tnum<W>, ParseTnumError> {
+    let inner = s
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or(ParseTnumError::Empty)?;
+    let (value, mask) = inner.split_once("; ").ok_or(ParseTnumError::Empty)?;
+    let (value, mask) = (parse_hex_word::<W>(value)?, parse_hex_word::<W>(mask)?);
+    if value & mask != W::ZERO {
+        return Err(ParseTnumError::NotCanonical);
+    }
+    Ok(Tnum::new(value, mask))
+}
+
+/// 逐位累积解析结果，从高位到低位
+struct TritBuilder<W> {
+    value: W,
+    mask: W,
+    bits: u32,
+}
+
+impl<W: TnumWord> TritBuilder<W> {
+    fn new() -> Self {
+        TritBuilder { value: W::ZERO, mask: W::ZERO, bits: 0 }
+    }
+
+    fn push(&mut self, c: char) -> Result<(), ParseTnumError> {
+        // 高位溢出的只能是已知的 0
+        if self.bits >= W::BITS && (self.value | self.mask) >> (W::BITS - 1) != W::ZERO {
+            return Err(ParseTnumError::TooLong);
+        }
+        self.value = self.value << 1;
+        self.mask = self.mask << 1;
+        match c {
+            '0' => {}
+            '1' => self.value = self.value | W::ONE,
+            'x' | 'X' => self.mask = self.mask | W::ONE,
+            _ => return Err(ParseTnumError::InvalidDigit(c)),
+        }
+        self.bits += 1;
+        Ok(())
+    }
+
+    fn push_nibble(&mut self, c: char) -> Result<(), ParseTnumError> {
+        if c == 'x' || c == 'X' {
+            return (0..4).try_for_each(|_| self.push('x'));
+        }
+        let d = c.to_digit(16).ok_or(ParseTnumError::InvalidDigit(c))?;
+        (0..4)
+            .rev()
+            .try_for_each(|j| self.push(if d >> j & 1 == 1 { '1' } else { '0' }))
+    }
+}
+
+impl<W: TnumWord> FromStr for Tnum<W> {
+    type Err = ParseTnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('(') {
+            return parse_strn(s);
+        }
+        let mut builder = TritBuilder::new();
+        if let Some(digits) = s.strip_prefix("0b") {
+            for c in digits.chars() {
+                builder.push(c)?;
+            }
+        } else if let Some(digits) = s.strip_prefix("0x") {
+            let mut chars = digits.chars();
+            while let Some(c) = chars.next() {
+                if c != '[' {
+                    builder.push_nibble(c)?;
+                    continue;
+                }
+                let mut group = 0;
+                loop {
+                    match chars.next() {
+                        Some(']') if group == 4 => break,
+                        Some(c) if group < 4 && c != ']' => builder.push(c)?,
+                        _ => return Err(ParseTnumError::InvalidGroup),
+                    }
+                    group += 1;
+                }
+            }
+        } else {
+            return Err(ParseTnumError::Empty);
+        }
+        if builder.bits == 0 {
+            return Err(ParseTnumError::Empty);
+        }
+        Ok(Tnum::new(builder.value, builder.mask))
+    }
+}
+
+#[test]
+fn test_tnum_fmt_roundtrip() {
+    let t: Tnum<u8> = Tnum::new(0b1000_0001, 0b0000_1100);
+    assert_eq!(t.to_string(), "0b1000xx01");
+    assert_eq!(format!("{:x}", t), "0x8[xx01]");
+    assert_eq!(format!("{:10}", t), "0b1000xx01");
+    assert_eq!(format!("{:4}", t), "0b1000xx01");
+    assert_eq!(format!("{:10}", Tnum::<u16>::new(1, 2)), "0b00000000x1");
+    assert_eq!(format!("{:3x}", t), "0x8[xx01]");
+    assert_eq!(format!("{:3x}", Tnum::<u16>::new(0x81, 0xc)), "0x08[xx01]");
+    let t: Tnum = Tnum::new(0x1200, 0xf0);
+    assert_eq!(format!("{:x}", t), "0x12x0");
+    assert_eq!(Tnum::<u64>::new(0, 0).to_string(), "0b0");
+    for bits in 0..=255u8 {
+        for mask in 0..=255u8 {
+            if bits & mask != 0 {
+                continue;
+            }
+            let t = Tnum::new(bits, mask);
+            for s in [t.to_string(), format!("{:x}", t), format!("{:8}", t), format!("{:2x}", t)] {
+                let back: Tnum<u8> = s.parse().unwrap();
+                assert_eq!(back, t, "{}", s);
+            }
+        }
+    }
+    let t: Tnum = "0x1x[10x1]f".parse().unwrap();
+    assert_eq!((t.value(), t.mask()), (0x109f, 0x0f20));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_tnum_sbin() {
+    let t: Tnum<u8> = Tnum::new(0b1000_0001, 0b0000_1100);
+    assert_eq!(tnum_sbin(0, t), "");
+    assert_eq!(tnum_sbin(1, t), "");
+    assert_eq!(tnum_sbin(4, t), "100");
+    assert_eq!(tnum_sbin(9, t), "1000xx01");
+    assert_eq!(tnum_sbin(100, t), "1000xx01");
+}
+
+#[test]
+fn test_tnum_parse_errors() {
+    assert_eq!("10x".parse::<Tnum>(), Err(ParseTnumError::Empty));
+    assert_eq!("0b".parse::<Tnum>(), Err(ParseTnumError::Empty));
+    assert_eq!("0b102".parse::<Tnum>(), Err(ParseTnumError::InvalidDigit('2')));
+    assert_eq!("0x[10x]".parse::<Tnum>(), Err(ParseTnumError::InvalidGroup));
+    assert_eq!("0x[10x".parse::<Tnum>(), Err(ParseTnumError::InvalidGroup));
+    assert_eq!("0b1xxxxxxxx".parse::<Tnum<u8>>(), Err(ParseTnumError::TooLong));
+    assert_eq!("0b000000001".parse::<Tnum<u8>>(), Ok(Tnum::new(1, 0)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_tnum_strn() {
+    // 摘自 verifier 日志
+    let t: Tnum = "(0x0; 0xff)".parse().unwrap();
+    assert_eq!(t, tnum_range(0, 255));
+    assert_eq!(tnum_strn(t), "(0x0; 0xff)");
+    let t: Tnum = "(0x4; 0xfffffffffffffff8)".parse().unwrap();
+    assert_eq!((t.value(), t.mask()), (4, !7));
+    assert_eq!(tnum_strn(t), "(0x4; 0xfffffffffffffff8)");
+    assert_eq!(tnum_strn(Tnum::<u64>::TOP), "(0x0; 0xffffffffffffffff)");
+    assert_eq!(tnum_strn(Tnum::<u128>::TOP), format!("(0x0; 0x{:x})", u128::MAX));
+    for t in crate::tnum::tnum_samples::<u16>(&[0, 1, 7, 8, 15]) {
+        assert_eq!(tnum_strn(t).parse::<Tnum<u16>>(), Ok(t));
+    }
+
+    assert_eq!("(0x1; 0x1)".parse::<Tnum>(), Err(ParseTnumError::NotCanonical));
+    assert_eq!("(0x100; 0x0)".parse::<Tnum<u8>>(), Err(ParseTnumError::TooLong));
+    assert_eq!("(0x0;0xff)".parse::<Tnum>(), Err(ParseTnumError::Empty));
+    assert_eq!("(0; 0xff)".parse::<Tnum>(), Err(ParseTnumError::Empty));
+    assert_eq!("(0x0; 0x+f)".parse::<Tnum>(), Err(ParseTnumError::InvalidDigit('+')));
+    assert_eq!("(0x0; 0xff".parse::<Tnum>(), Err(ParseTnumError::Empty));
+}
+
+#[test]
+fn test_tnum_strn_display() {
+    // 不依赖 alloc 的适配器
+    let t = tnum_range(0u8, 15);
+    assert_eq!(format!("var_off={}", TnumStrn(t)), "var_off=(0x0; 0xf)");
+    assert_eq!(format!("{}", TnumStrn(Tnum::<u64>::BOTTOM)), "(0xffffffffffffffff; 0xffffffffffffffff)");
+}
@@ -0,0 +1,95 @@
+//! Registry of the tnum multiplication methods under comparison
+//!
+//! Every `tnum-bench` subcommand iterates [MUL_METHODS], so adding a
+//! multiplier is one entry here.
+
+use crate::tnum::{tnum_mul, tnum_mul_opt, xtnum_mul_high_top, xtnum_mul_top, Tnum};
+
+/// 作为基准的内核 C 实现的名字
+pub const REFERENCE_METHOD: &str = "C_tnum_mul";
+
+/// 一个乘法实现及其元数据
+#[derive(Debug, Clone, Copy)]
+pub struct MulMethod {
+    /// 结果文件与报告中使用的名字
+    pub name: &'static str,
+    pub func: fn(Tnum, Tnum) -> Tnum,
+    /// 是否声称可靠：结果总包含所有具体乘积（由测试检查）
+    pub sound: bool,
+    pub description: &'static str,
+}
+
+/// 所有参与比较的 Rust 乘法实现
+pub const MUL_METHODS: &[MulMethod] = &[
+    MulMethod {
+        name: "tnum_mul",
+        func: tnum_mul,
+        sound: true,
+        description: "port of the kernel shift-and-add tnum_mul",
+    },
+    MulMethod {
+        name: "tnum_mul_opt",
+        func: tnum_mul_opt,
+        sound: true,
+        description: "tnum_mul with constant and operand-order shortcuts",
+    },
+    MulMethod {
+        name: "xtnum_mul_top",
+        func: xtnum_mul_top,
+        sound: true,
+        description: "extended tnum_mul splitting on the first unknown bit",
+    },
+    MulMethod {
+        name: "xtnum_mul_high_top",
+        func: xtnum_mul_high_top,
+        sound: true,
+        description: "extended tnum_mul splitting on the highest unknown bit",
+    },
+];
+
+/// 按名字查找
+pub fn find_method(name: &str) -> Option<&'static MulMethod> {
+    MUL_METHODS.iter().find(|m| m.name == name)
+}
+
+#[test]
+fn test_mul_methods() {
+    use crate::tnum::{tnum_const, tnum_gamma, tnum_in, tnum_samples};
+
+    let names: Vec<&str> = MUL_METHODS.iter().map(|m| m.name).collect();
+    for (i, name) in names.iter().enumerate() {
+        assert!(!names[..i].contains(name), "duplicate method {}", name);
+        assert_ne!(*name, REFERENCE_METHOD);
+        assert_eq!(find_method(name).unwrap().name, *name);
+    }
+    assert!(find_method(REFERENCE_METHOD).is_none());
+
+    // 低 4 位上的乘积不会溢出，可以逐个检查具体值
+    let samples: Vec<Tnum> = tnum_samples(&[0, 1, 2, 3]);
+    for m in MUL_METHODS {
+        let unsound = samples.iter().any(|&a| {
+            samples.iter().any(|&b| {
+                let r = (m.func)(a, b);
+                tnum_gamma(a)
+                    .any(|x| tnum_gamma(b).any(|y| !tnum_in(r, tnum_const(x * y))))
+            })
+        });
+        assert_eq!(!unsound, m.sound, "{} soundness claim", m.name);
+    }
+
+    // 最高位参与时乘积回绕，可靠的实现要包含回绕后的乘积，且不能 panic
+    let high: Vec<Tnum> = tnum_samples(&[0, 1, 62, 63]);
+    for m in MUL_METHODS.iter().filter(|m| m.sound) {
+        for &a in &high {
+            for &b in &high {
+                let r = (m.func)(a, b);
+                for x in tnum_gamma(a) {
+                    for y in tnum_gamma(b) {
+                        assert!(tnum_in(r, tnum_const(x.wrapping_mul(y))), "{} {:?} {:?}", m.name, a, b);
+                    }
+                }
+            }
+        }
+    }
+    assert!(MUL_METHODS.iter().all(|m| (m.func)(Tnum::TOP, Tnum::TOP).is_top()));
+}
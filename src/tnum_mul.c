@@ -16,13 +16,17 @@ typedef struct {
 
 int main(int argc, char *argv[]) {
     if (argc < 2) {
-        printf("Usage: %s <rust_test_cases.json>\n", argv[0]);
+        printf("Usage: %s <rust_test_cases.json> [iterations] [output.json]\n", argv[0]);
         return 1;
     }
 
     const char *input_file = argv[1];
-    const char *output_file = "./build/c_test_results.json";
     const int iterations = (argc > 2) ? atoi(argv[2]) : 1000;
+    const char *output_file = (argc > 3) ? argv[3] : "./build/c_test_results.json";
+    if (iterations <= 0) {
+        fprintf(stderr, "Invalid iterations: %s\n", argv[2]);
+        return 1;
+    }
     
     // 读取JSON文件
     FILE *fp = fopen(input_file, "r");
@@ -144,6 +148,10 @@ int main(int argc, char *argv[]) {
         printf("\n结果已保存到：%s\n", output_file);
     } else {
         perror("Failed to open output file");
+        json_object_put(root_obj);
+        json_object_put(output_array);
+        free(json_str);
+        return 1;
     }
 
     // 清理资源
@@ -0,0 +1,184 @@
+//! Random tnums for the test harnesses
+//!
+//! [TnumGen] draws 64-bit tnums restricted to the low `width` bits from a
+//! seeded rng, so a run can be replayed from its seed. Every draw is
+//! canonical (value and mask never overlap).
+
+use crate::tnum::{tnum_const, tnum_range, Tnum};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// 生成 tnum 的分布
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TnumDist {
+    /// 每位独立：以 unknown_density 的概率未知，否则等概率为 0 或 1
+    Uniform,
+    /// 随机常数上只有 1 到 3 个未知位
+    SparseUnknown,
+    /// 3/4 是常数，其余同 Uniform
+    ConstantHeavy,
+    /// 随机区间 [min, max] 经 tnum_range 得到的 tnum
+    Range,
+    /// 每次随机选一种上面的分布
+    Mixed,
+}
+
+/// 生成器的配置
+#[derive(Debug, Clone, Copy)]
+pub struct TnumGenConfig {
+    /// 有效位数（1..=64），更高的位都是已知的 0
+    pub width: u32,
+    pub seed: u64,
+    /// Uniform（以及 ConstantHeavy 的非常数部分）中每位未知的概率
+    pub unknown_density: f64,
+    pub distribution: TnumDist,
+    /// 改为生成边界情况的概率：0、top、单个位、接近溢出的值
+    pub edge_rate: f64,
+}
+
+impl Default for TnumGenConfig {
+    fn default() -> Self {
+        TnumGenConfig {
+            width: 64,
+            seed: 0,
+            unknown_density: 1.0 / 3.0,
+            distribution: TnumDist::Mixed,
+            edge_rate: 0.1,
+        }
+    }
+}
+
+/// 可复现的随机 tnum 生成器，也可以当作无限迭代器使用
+pub struct TnumGen {
+    config: TnumGenConfig,
+    rng: StdRng,
+}
+
+impl TnumGen {
+    pub fn new(config: TnumGenConfig) -> Self {
+        assert!((1..=64).contains(&config.width), "width must be in 1..=64");
+        TnumGen { config, rng: StdRng::seed_from_u64(config.seed) }
+    }
+
+    pub fn config(&self) -> &TnumGenConfig {
+        &self.config
+    }
+
+    /// 低 width 位全 1
+    fn top(&self) -> u64 {
+        u64::MAX >> (64 - self.config.width)
+    }
+
+    fn bits(&mut self) -> u64 {
+        self.rng.random::<u64>() & self.top()
+    }
+
+    fn bit(&mut self) -> u64 {
+        1 << self.rng.random_range(0..self.config.width)
+    }
+
+    /// 每位以概率 p 置 1
+    fn sparse_bits(&mut self, p: f64) -> u64 {
+        (0..self.config.width).filter(|_| self.rng.random_bool(p)).fold(0, |m, i| m | 1 << i)
+    }
+
+    fn uniform(&mut self) -> Tnum {
+        let mask = self.sparse_bits(self.config.unknown_density);
+        Tnum::new(self.bits() & !mask, mask)
+    }
+
+    /// 边界情况
+    fn edge_case(&mut self) -> Tnum {
+        let top = self.top();
+        match self.rng.random_range(0..6) {
+            0 => tnum_const(0),
+            1 => Tnum::new(0, top),
+            2 => tnum_const(self.bit()),
+            3 => Tnum::new(0, self.bit()),
+            // 接近溢出的常数
+            4 => tnum_const(top - self.rng.random_range(0..4).min(top)),
+            // 只有最高位未知：{0x7f.., 0xff..}
+            _ => Tnum::new(top >> 1, top ^ (top >> 1)),
+        }
+    }
+
+    /// 下一个 tnum
+    pub fn next_tnum(&mut self) -> Tnum {
+        if self.rng.random_bool(self.config.edge_rate) {
+            return self.edge_case();
+        }
+        let dist = match self.config.distribution {
+            TnumDist::Mixed => [
+                TnumDist::Uniform,
+                TnumDist::SparseUnknown,
+                TnumDist::ConstantHeavy,
+                TnumDist::Range,
+            ][self.rng.random_range(0..4)],
+            dist => dist,
+        };
+        match dist {
+            TnumDist::SparseUnknown => {
+                let mask = (0..self.rng.random_range(1..=3)).fold(0, |m, _| m | self.bit());
+                Tnum::new(self.bits() & !mask, mask)
+            }
+            TnumDist::ConstantHeavy if self.rng.random_bool(0.75) => tnum_const(self.bits()),
+            TnumDist::Range => {
+                // 区间长度的位数也随机，短区间与长区间都能取到
+                let min = self.bits();
+                let span = self.bits() >> self.rng.random_range(0..self.config.width);
+                tnum_range(min, min.saturating_add(span).min(self.top()))
+            }
+            _ => self.uniform(),
+        }
+    }
+}
+
+impl Iterator for TnumGen {
+    type Item = Tnum;
+
+    fn next(&mut self) -> Option<Tnum> {
+        Some(self.next_tnum())
+    }
+}
+
+#[test]
+fn test_tnum_gen() {
+    let dists = [
+        TnumDist::Uniform,
+        TnumDist::SparseUnknown,
+        TnumDist::ConstantHeavy,
+        TnumDist::Range,
+        TnumDist::Mixed,
+    ];
+    for distribution in dists {
+        for width in [1, 8, 33, 64] {
+            let config = TnumGenConfig { width, seed: 7, distribution, ..Default::default() };
+            let a: Vec<Tnum> = TnumGen::new(config).take(500).collect();
+            let b: Vec<Tnum> = TnumGen::new(config).take(500).collect();
+            // 同一个种子得到同样的序列
+            assert!(a.iter().zip(&b).all(|(x, y)| (x.value(), x.mask()) == (y.value(), y.mask())));
+            let top = u64::MAX >> (64 - width);
+            for t in &a {
+                assert_eq!(t.value() & t.mask(), 0, "{:?}", t);
+                assert_eq!((t.value() | t.mask()) & !top, 0, "{:?} width {}", t, width);
+            }
+            if width > 8 {
+                assert!(a.iter().any(|t| (t.value() | t.mask()) >> 8 != 0), "{:?}", distribution);
+            }
+        }
+    }
+
+    let uniform = |unknown_density, edge_rate| TnumGenConfig {
+        unknown_density,
+        edge_rate,
+        distribution: TnumDist::Uniform,
+        ..Default::default()
+    };
+    assert!(TnumGen::new(uniform(0.0, 0.0)).take(100).all(|t| t.is_const()));
+    assert!(TnumGen::new(uniform(1.0, 0.0)).take(100).all(|t| t.is_top()));
+    // 全部为边界情况时 0 与 top 都会出现
+    let edges: Vec<Tnum> = TnumGen::new(uniform(0.5, 1.0)).take(200).collect();
+    assert!(edges.iter().any(|t| t.as_const() == Some(0)));
+    assert!(edges.iter().any(|t| t.is_top()));
+    assert!(edges.iter().any(|t| t.mask() == 1 << 63 && t.value() == u64::MAX >> 1));
+}
@@ -0,0 +1,110 @@
+//! Serde support for [Tnum]
+//!
+//! `Tnum` itself (de)serializes as a `{ "value": .., "mask": .. }` object,
+//! which is also what the C harness reads and writes. Fields can pick a
+//! text form instead with `#[serde(with = "...")]`:
+//!
+//! - [kernel]: the verifier-log string `(0x0; 0xff)`
+//! - [trits]: the binary trit string `0b10xx01`
+//!
+//! Every representation rejects a value that overlaps the mask, so a
+//! deserialized tnum is always canonical (and never bottom).
+
+use crate::tnum::{Tnum, TnumWord};
+use crate::tnum_fmt::ParseTnumError;
+use alloc::string::String;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 对象表示
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Tnum")]
+struct Raw<W> {
+    value: W,
+    mask: W,
+}
+
+impl<W: TnumWord + Serialize> Serialize for Tnum<W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Raw { value: self.value(), mask: self.mask() }.serialize(serializer)
+    }
+}
+
+impl<'de, W: TnumWord + Deserialize<'de>> Deserialize<'de> for Tnum<W> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Raw::<W>::deserialize(deserializer)?;
+        if raw.value & raw.mask != W::ZERO {
+            return Err(D::Error::custom(ParseTnumError::NotCanonical));
+        }
+        Ok(Tnum::new(raw.value, raw.mask))
+    }
+}
+
+/// 从字符串解析，两种文本表示共用
+fn deserialize_str<'de, D: Deserializer<'de>, W: TnumWord>(
+    deserializer: D,
+) -> Result<Tnum<W>, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(D::Error::custom)
+}
+
+/// `(0x..; 0x..)` 字符串表示，用于 `#[serde(with = "tnum::tnum_serde::kernel")]`
+pub mod kernel {
+    use super::*;
+    use crate::tnum_fmt::tnum_strn;
+
+    pub fn serialize<S: Serializer, W: TnumWord>(a: &Tnum<W>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&tnum_strn(*a))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, W: TnumWord>(deserializer: D) -> Result<Tnum<W>, D::Error> {
+        deserialize_str(deserializer)
+    }
+}
+
+/// `0b10xx01` 字符串表示，用于 `#[serde(with = "tnum::tnum_serde::trits")]`
+pub mod trits {
+    use super::*;
+
+    pub fn serialize<S: Serializer, W: TnumWord>(a: &Tnum<W>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(a)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, W: TnumWord>(deserializer: D) -> Result<Tnum<W>, D::Error> {
+        deserialize_str(deserializer)
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Reprs {
+    object: Tnum,
+    #[serde(with = "kernel")]
+    kernel: Tnum,
+    #[serde(with = "trits")]
+    trits: Tnum<u8>,
+}
+
+#[test]
+fn test_tnum_serde() {
+    let r = Reprs {
+        object: Tnum::new(4, 0xf0),
+        kernel: Tnum::new(0, 0xff),
+        trits: Tnum::new(0b1000_0001, 0b0000_1100),
+    };
+    let json = serde_json::to_string(&r).unwrap();
+    assert_eq!(
+        json,
+        r#"{"object":{"value":4,"mask":240},"kernel":"(0x0; 0xff)","trits":"0b1000xx01"}"#
+    );
+    assert_eq!(serde_json::from_str::<Reprs>(&json).unwrap(), r);
+    // 三进制字符串也接受十六进制写法
+    let json = r#"{"object":{"value":4,"mask":240},"kernel":"(0x0; 0xff)","trits":"0x8[xx01]"}"#;
+    assert_eq!(serde_json::from_str::<Reprs>(json).unwrap(), r);
+
+    // 非规范的输入被拒绝
+    assert!(serde_json::from_str::<Tnum>(r#"{"value":1,"mask":1}"#).is_err());
+    let json = r#"{"object":{"value":4,"mask":240},"kernel":"(0x1; 0xff)","trits":"0b1"}"#;
+    assert!(serde_json::from_str::<Reprs>(json).is_err());
+    assert!(serde_json::from_str::<Tnum<u8>>(r#"{"value":256,"mask":0}"#).is_err());
+}
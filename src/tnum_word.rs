@@ -0,0 +1,138 @@
+//! Machine words usable as the carrier of a tnum
+
+use core::fmt::Debug;
+use core::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Not, Rem, Shl, Shr, Sub};
+
+/// 无符号机器字（u8/u16/u32/u64/u128），tnum 的 value 与 mask 都是该类型
+pub trait TnumWord:
+    Copy
+    + Eq
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    /// 位宽
+    const BITS: u32;
+    /// 0
+    const ZERO: Self;
+    /// 1
+    const ONE: Self;
+    /// 全 1
+    const MAX: Self;
+    /// 同宽度的有符号整数
+    type Signed: Copy + Eq + Ord + Debug;
+
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn wrapping_sub(self, rhs: Self) -> Self;
+    fn wrapping_mul(self, rhs: Self) -> Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn leading_zeros(self) -> u32;
+    fn leading_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+    fn count_ones(self) -> u32;
+    fn is_power_of_two(self) -> bool;
+    /// 把最高位当作符号位的算术右移
+    fn arshift(self, shift: u32) -> Self;
+    /// 按补码解释为有符号数
+    fn to_signed(self) -> Self::Signed;
+    /// 截断到本类型
+    fn from_u128(v: u128) -> Self;
+    /// 零扩展到 u128
+    fn to_u128(self) -> u128;
+}
+
+macro_rules! impl_tnum_word {
+    ($($t:ty => $s:ty),*) => {
+        $(
+            impl TnumWord for $t {
+                const BITS: u32 = <$t>::BITS;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$t>::MAX;
+                type Signed = $s;
+
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    <$t>::wrapping_add(self, rhs)
+                }
+                fn wrapping_sub(self, rhs: Self) -> Self {
+                    <$t>::wrapping_sub(self, rhs)
+                }
+                fn wrapping_mul(self, rhs: Self) -> Self {
+                    <$t>::wrapping_mul(self, rhs)
+                }
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_div(self, rhs)
+                }
+                fn checked_rem(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_rem(self, rhs)
+                }
+                fn leading_zeros(self) -> u32 {
+                    <$t>::leading_zeros(self)
+                }
+                fn leading_ones(self) -> u32 {
+                    <$t>::leading_ones(self)
+                }
+                fn trailing_zeros(self) -> u32 {
+                    <$t>::trailing_zeros(self)
+                }
+                fn count_ones(self) -> u32 {
+                    <$t>::count_ones(self)
+                }
+                fn is_power_of_two(self) -> bool {
+                    <$t>::is_power_of_two(self)
+                }
+                fn arshift(self, shift: u32) -> Self {
+                    ((self as $s) >> shift) as $t
+                }
+                fn to_signed(self) -> $s {
+                    self as $s
+                }
+                fn from_u128(v: u128) -> Self {
+                    v as $t
+                }
+                fn to_u128(self) -> u128 {
+                    self as u128
+                }
+            }
+        )*
+    };
+}
+
+impl_tnum_word!(u8 => i8, u16 => i16, u32 => i32, u64 => i64, u128 => i128);
+
+/// 存在两倍宽度的机器字（u8/u16/u32/u64），用于不回绕的乘法
+pub trait TnumWideWord: TnumWord {
+    /// 两倍宽度的字
+    type Wide: TnumWord;
+}
+
+macro_rules! impl_tnum_wide_word {
+    ($($t:ty => $w:ty),*) => {
+        $(
+            impl TnumWideWord for $t {
+                type Wide = $w;
+            }
+        )*
+    };
+}
+
+impl_tnum_wide_word!(u8 => u16, u16 => u32, u32 => u64, u64 => u128);